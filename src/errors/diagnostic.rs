@@ -0,0 +1,79 @@
+use std::fmt;
+
+use crate::models::span::Span;
+
+/// How serious a problem is.
+#[derive(Debug, PartialEq, Clone, Copy, Default)]
+pub enum Severity {
+    #[default]
+    Error,
+    Warning,
+}
+
+impl fmt::Display for Severity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Severity::Error => write!(f, "error"),
+            Severity::Warning => write!(f, "warning")
+        }
+    }
+}
+
+/// Represents a problem to report to the user: a primary message pointing to a location,
+/// with optional notes giving context and a help message suggesting a fix.
+#[derive(Debug, PartialEq, Clone)]
+pub struct Diagnostic {
+    severity: Severity,
+    code: Option<String>,
+    message: String,
+    span: Span,
+    notes: Vec<String>,
+    help: Option<String>,
+}
+
+impl Diagnostic {
+    /// Creates a new `Diagnostic` with the specified message and location.
+    ///
+    /// ## Arguments
+    ///
+    /// - `message` - The primary message.
+    /// - `span` - The location the message refers to.
+    ///
+    /// ## Returns
+    ///
+    /// A new `Diagnostic` instance without code, notes or help.
+    pub fn new(message: &str, span: Span) -> Diagnostic {
+        Diagnostic { severity: Severity::Error, code: None, message: message.to_string(), span, notes: Vec::new(), help: None }
+    }
+
+    /// Sets how serious the problem is, `Severity::Error` by default.
+    pub fn with_severity(mut self, severity: Severity) -> Diagnostic {
+        self.severity = severity;
+        self
+    }
+
+    /// Sets the stable code identifying the kind of problem.
+    pub fn with_code(mut self, code: &str) -> Diagnostic {
+        self.code = Some(code.to_string());
+        self
+    }
+
+    /// Adds a note giving context about the problem.
+    pub fn with_note(mut self, note: &str) -> Diagnostic {
+        self.notes.push(note.to_string());
+        self
+    }
+
+    /// Sets the help message suggesting how to fix the problem.
+    pub fn with_help(mut self, help: &str) -> Diagnostic {
+        self.help = Some(help.to_string());
+        self
+    }
+
+    pub fn severity(&self) -> Severity { self.severity }
+    pub fn code(&self) -> Option<&str> { self.code.as_deref() }
+    pub fn message(&self) -> &str { &self.message }
+    pub fn span(&self) -> &Span { &self.span }
+    pub fn notes(&self) -> &Vec<String> { &self.notes }
+    pub fn help(&self) -> Option<&str> { self.help.as_deref() }
+}
\ No newline at end of file
@@ -0,0 +1,84 @@
+use std::fmt;
+use std::io;
+
+use crate::models::source::SourceFile;
+use super::error::SicompilerError;
+
+/// Collects every problem found while compiling a program, so all of them
+/// can be reported in a single run instead of stopping at the first one.
+///
+/// It also keeps the files that were read, so the problems can be rendered with their source lines.
+#[derive(Debug, Default)]
+pub struct Diagnostics {
+    errors: Vec<SicompilerError>,
+    sources: Vec<SourceFile>,
+}
+
+impl Diagnostics {
+    /// Creates an empty `Diagnostics` collector.
+    pub fn new() -> Diagnostics {
+        Diagnostics { errors: Vec::new(), sources: Vec::new() }
+    }
+
+    pub fn errors(&self) -> &Vec<SicompilerError> { &self.errors }
+    pub fn len(&self) -> usize { self.errors.len() }
+    pub fn is_empty(&self) -> bool { self.errors.is_empty() }
+
+    /// Adds an error to the collector.
+    pub fn push(&mut self, error: SicompilerError) {
+        self.errors.push(error);
+    }
+
+    /// Keeps the content of a file, replacing any previous file with the same path.
+    pub fn add_source(&mut self, source: SourceFile) {
+        self.sources.retain(|known: &SourceFile| known.path() != source.path());
+        self.sources.push(source);
+    }
+
+    /// Returns the content of the file with the specified path, if it was read.
+    pub fn source(&self, path: &str) -> Option<&SourceFile> {
+        self.sources.iter().find(|source: &&SourceFile| source.path() == path)
+    }
+
+    /// Returns a one-line summary with the number of errors found.
+    pub fn summary(&self) -> String {
+        match self.errors.len() {
+            1 => "Compilation failed due to 1 error".to_string(),
+            count => format!("Compilation failed due to {} errors", count)
+        }
+    }
+
+    /// Turns the collector into a `Result`, failing if any error was collected.
+    ///
+    /// ## Returns
+    ///
+    /// - `Ok(())` if there are no errors.
+    /// - `Err(Diagnostics)` with every collected error otherwise.
+    pub fn into_result(self) -> Result<(), Diagnostics> {
+        if self.is_empty() { Ok(()) } else { Err(self) }
+    }
+}
+
+impl fmt::Display for Diagnostics {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for error in &self.errors {
+            writeln!(f, "{}", error)?;
+        }
+
+        write!(f, "{}", self.summary())
+    }
+}
+
+impl std::error::Error for Diagnostics {}
+
+impl From<SicompilerError> for Diagnostics {
+    fn from(value: SicompilerError) -> Self {
+        Diagnostics { errors: vec![value], sources: Vec::new() }
+    }
+}
+
+impl From<io::Error> for Diagnostics {
+    fn from(value: io::Error) -> Self {
+        Diagnostics::from(SicompilerError::Io(value))
+    }
+}
\ No newline at end of file
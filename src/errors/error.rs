@@ -1,24 +1,404 @@
 use std::io;
 use std::fmt;
 
+use crate::models::span::Span;
+use super::diagnostic::Diagnostic;
+
+/// The ways a number can be written in a program, listed in the errors of the values that are not numbers.
+const NUMBER_FORMS: &str = "hex, 0x…, 0b…, 0o…, d'…, 'c'";
+
 /// Custom error type, encompassing different error scenarios.
+///
+/// Every variant has a stable code (see `SicompilerError::code`) that does not change
+/// when its message is reworded, and a long-form description available with `--explain`.
 #[derive(Debug)]
 pub enum SicompilerError {
     /// Represents I/O errors
     Io(io::Error),
-    /// Represents errors during the tokenization process.
-    TokenizationError(String),
-    /// Represents validation errors.
-    ValidationError(String)
+    /// The program file is empty.
+    EmptyFile { span: Span },
+    /// The program has less than three sections.
+    MissingSection { found: usize, span: Span },
+    /// The program has more than three sections.
+    TooManySections { found: usize, span: Span },
+    /// A line of the variables section is not written as `<DIR VALUE [NAME]>`.
+    InvalidVariableFormat { span: Span },
+    /// The init section does not have an address.
+    MissingInitAddress { span: Span },
+    /// The init section has more than one address.
+    DuplicateInitAddress { first: String, span: Span },
+    /// The repertoire does not have a microprogram section.
+    MissingMicroprogram { span: Span },
+    /// The repertoire has more instructions than the machine can encode.
+    RepertoireTooLarge { found: usize, max: usize, span: Span },
+    /// The program does not have any variables or instructions.
+    EmptySection { span: Span },
+    /// The dir or the value of a variable is not a number.
+    NonHexVariable { dir: String, value: String, span: Span },
+    /// The init address is not a number.
+    NonHexInit { dir: String, span: Span },
+    /// The mnemonic of an instruction is not in the repertoire.
+    UnknownMnemonic { mnemonic: String, span: Span },
+    /// An instruction has a different number of operands than its repertoire entry.
+    WrongOperandCount { mnemonic: String, expected: usize, found: usize, span: Span, entry: Span },
+    /// An operand of an instruction is not a number.
+    NonHexOperand { mnemonic: String, operand: String, span: Span, entry: Span },
+    /// A control word of the repertoire is not in hex base.
+    NonHexControlWord { word: String, span: Span },
+    /// A control word of the repertoire has more bits than the control signals.
+    ControlWordTooWide { word: String, width: u32, span: Span },
+    /// A line of the microprogram section is not written as `CB <WORD>`.
+    MalformedMicroprogramLine { span: Span },
+    /// A variable or an instruction is placed outside the memory of the machine.
+    AddressOutOfRange { address: String, max: u64, span: Span },
+    /// The simulator fetched a word whose opcode is not in the repertoire.
+    InvalidOpcode { opcode: u64, address: u64 },
+    /// The simulated program ran more microsteps than allowed without halting.
+    StepLimitExceeded { steps: usize },
+    /// A label or a variable name is defined more than once.
+    DuplicateSymbol { name: String, first: Span, span: Span },
+    /// An operand names a label or a variable that is not defined.
+    UndefinedSymbol { name: String, span: Span },
+    /// A label or a variable name can not be used as a name.
+    InvalidSymbolName { name: String, span: Span },
+    /// A memory image can not be read in the format it was given in.
+    MalformedImage { detail: String, span: Span },
+    /// A program checked with `fmt --check` is not formatted.
+    Unformatted { span: Span },
+    /// An output would overwrite one of the files that were read.
+    OverwriteInput { path: String, span: Span },
+    /// The repertoire does not declare any instruction.
+    EmptyRepertoire { span: Span },
+    /// A line of the instructions section of the repertoire is not written as `<MNEMONIC SIGNATURE [WORDS]>`.
+    MalformedInstructionLine { span: Span },
+    /// The operand signature of a repertoire instruction is not `true`, `false` or a list of operands.
+    InvalidSignature { signature: String, span: Span },
+    /// A mnemonic is declared more than once in the repertoire.
+    DuplicateMnemonic { mnemonic: String, first: Span, span: Span },
+    /// The repertoire has more than two `$` separators.
+    ExtraRepertoireSeparator { span: Span },
+    /// The operands of a repertoire instruction have more bits than the operand field.
+    SignatureTooWide { signature: String, width: u32, max: u32, span: Span },
+    /// An operand of an instruction is not of the kind its repertoire entry declares.
+    WrongOperandKind { mnemonic: String, operand: String, expected: String, span: Span, entry: Span },
+    /// An operand of an instruction does not fit in the bits its repertoire entry declares.
+    OperandOutOfRange { mnemonic: String, operand: String, width: u32, span: Span, entry: Span },
+    /// The initial value of a variable does not fit in the data bits.
+    ValueOutOfRange { value: String, bits: u32, max: u64, span: Span },
+    /// A line of the repertoire header is not written as `<ADDRESS BITS>` or `<DATA BITS>`.
+    MalformedHeaderLine { span: Span },
+    /// A width of the repertoire header is not a number of bits the machine can have.
+    InvalidWidth { name: String, width: String, max: u32, span: Span },
+    /// An operand or a data value is not a well written expression.
+    InvalidExpression { expression: String, span: Span },
+    /// An expression has a negative value, or a value that does not fit in 64 bits, in any of its steps.
+    ExpressionOverflow { expression: String, span: Span },
+    /// An expression divides by zero.
+    DivisionByZero { expression: String, span: Span },
+    /// A constant is not defined as `.equ NAME VALUE` or `NAME = VALUE`.
+    MalformedConstant { span: Span },
+    /// A variable or an instruction is placed at an address another one already takes.
+    OverlappingAddress { address: String, first: Span, span: Span },
+}
+
+impl SicompilerError {
+    /// Returns the stable code of the error.
+    pub fn code(&self) -> &'static str {
+        match self {
+            SicompilerError::Io(_) => "SC0001",
+            SicompilerError::EmptyFile { .. } => "SC0002",
+            SicompilerError::MissingSection { .. } => "SC0003",
+            SicompilerError::TooManySections { .. } => "SC0004",
+            SicompilerError::InvalidVariableFormat { .. } => "SC0005",
+            SicompilerError::MissingInitAddress { .. } => "SC0006",
+            SicompilerError::DuplicateInitAddress { .. } => "SC0007",
+            SicompilerError::MissingMicroprogram { .. } => "SC0008",
+            SicompilerError::RepertoireTooLarge { .. } => "SC0009",
+            SicompilerError::EmptySection { .. } => "SC0010",
+            SicompilerError::NonHexVariable { .. } => "SC0011",
+            SicompilerError::NonHexInit { .. } => "SC0012",
+            SicompilerError::UnknownMnemonic { .. } => "SC0013",
+            SicompilerError::WrongOperandCount { .. } => "SC0014",
+            SicompilerError::NonHexOperand { .. } => "SC0015",
+            SicompilerError::NonHexControlWord { .. } => "SC0016",
+            SicompilerError::ControlWordTooWide { .. } => "SC0017",
+            SicompilerError::MalformedMicroprogramLine { .. } => "SC0018",
+            SicompilerError::AddressOutOfRange { .. } => "SC0019",
+            SicompilerError::InvalidOpcode { .. } => "SC0020",
+            SicompilerError::StepLimitExceeded { .. } => "SC0021",
+            SicompilerError::DuplicateSymbol { .. } => "SC0022",
+            SicompilerError::UndefinedSymbol { .. } => "SC0023",
+            SicompilerError::InvalidSymbolName { .. } => "SC0024",
+            SicompilerError::MalformedImage { .. } => "SC0025",
+            SicompilerError::Unformatted { .. } => "SC0026",
+            SicompilerError::OverwriteInput { .. } => "SC0027",
+            SicompilerError::EmptyRepertoire { .. } => "SC0028",
+            SicompilerError::MalformedInstructionLine { .. } => "SC0029",
+            SicompilerError::InvalidSignature { .. } => "SC0030",
+            SicompilerError::DuplicateMnemonic { .. } => "SC0031",
+            SicompilerError::ExtraRepertoireSeparator { .. } => "SC0032",
+            SicompilerError::SignatureTooWide { .. } => "SC0033",
+            SicompilerError::WrongOperandKind { .. } => "SC0034",
+            SicompilerError::OperandOutOfRange { .. } => "SC0035",
+            SicompilerError::ValueOutOfRange { .. } => "SC0036",
+            SicompilerError::MalformedHeaderLine { .. } => "SC0037",
+            SicompilerError::InvalidWidth { .. } => "SC0038",
+            SicompilerError::InvalidExpression { .. } => "SC0039",
+            SicompilerError::ExpressionOverflow { .. } => "SC0040",
+            SicompilerError::DivisionByZero { .. } => "SC0041",
+            SicompilerError::MalformedConstant { .. } => "SC0042",
+            SicompilerError::OverlappingAddress { .. } => "SC0043",
+        }
+    }
+
+    /// Returns the location of the error, if it has one.
+    pub fn span(&self) -> Option<&Span> {
+        match self {
+            SicompilerError::Io(_)
+            | SicompilerError::InvalidOpcode { .. }
+            | SicompilerError::StepLimitExceeded { .. } => None,
+            SicompilerError::EmptyFile { span }
+            | SicompilerError::MissingSection { span, .. }
+            | SicompilerError::TooManySections { span, .. }
+            | SicompilerError::InvalidVariableFormat { span }
+            | SicompilerError::MissingInitAddress { span }
+            | SicompilerError::DuplicateInitAddress { span, .. }
+            | SicompilerError::MissingMicroprogram { span }
+            | SicompilerError::RepertoireTooLarge { span, .. }
+            | SicompilerError::EmptySection { span }
+            | SicompilerError::NonHexVariable { span, .. }
+            | SicompilerError::NonHexInit { span, .. }
+            | SicompilerError::UnknownMnemonic { span, .. }
+            | SicompilerError::WrongOperandCount { span, .. }
+            | SicompilerError::NonHexOperand { span, .. }
+            | SicompilerError::NonHexControlWord { span, .. }
+            | SicompilerError::ControlWordTooWide { span, .. }
+            | SicompilerError::MalformedMicroprogramLine { span }
+            | SicompilerError::AddressOutOfRange { span, .. }
+            | SicompilerError::DuplicateSymbol { span, .. }
+            | SicompilerError::UndefinedSymbol { span, .. }
+            | SicompilerError::InvalidSymbolName { span, .. }
+            | SicompilerError::MalformedImage { span, .. }
+            | SicompilerError::Unformatted { span }
+            | SicompilerError::OverwriteInput { span, .. }
+            | SicompilerError::EmptyRepertoire { span }
+            | SicompilerError::MalformedInstructionLine { span }
+            | SicompilerError::InvalidSignature { span, .. }
+            | SicompilerError::DuplicateMnemonic { span, .. }
+            | SicompilerError::ExtraRepertoireSeparator { span }
+            | SicompilerError::SignatureTooWide { span, .. }
+            | SicompilerError::WrongOperandKind { span, .. }
+            | SicompilerError::OperandOutOfRange { span, .. }
+            | SicompilerError::ValueOutOfRange { span, .. }
+            | SicompilerError::MalformedHeaderLine { span }
+            | SicompilerError::InvalidWidth { span, .. }
+            | SicompilerError::InvalidExpression { span, .. }
+            | SicompilerError::ExpressionOverflow { span, .. }
+            | SicompilerError::DivisionByZero { span, .. }
+            | SicompilerError::MalformedConstant { span }
+            | SicompilerError::OverlappingAddress { span, .. } => Some(span),
+        }
+    }
+
+    /// Returns the primary message of the error.
+    pub fn message(&self) -> String {
+        match self {
+            SicompilerError::Io(err) => format!("I/O error: {}", err),
+            SicompilerError::EmptyFile { .. } => "The file is empty".to_string(),
+            SicompilerError::MissingSection { found, .. } | SicompilerError::TooManySections { found, .. } =>
+                format!("Invalid number of sections, must be 3 but get {}", found),
+            SicompilerError::InvalidVariableFormat { .. } => "Invalid variable format, the correct way is <DIR VALUE [NAME]>".to_string(),
+            SicompilerError::MissingInitAddress { .. } => "There is no any Init address".to_string(),
+            SicompilerError::DuplicateInitAddress { .. } => "There is more than one Init address".to_string(),
+            SicompilerError::MissingMicroprogram { .. } =>
+                "Invalid repertoire structure, the file must contain a microprogram section".to_string(),
+            SicompilerError::RepertoireTooLarge { found, max, .. } =>
+                format!("Invalid number of instructions, the max is {} but get {}", max, found),
+            SicompilerError::EmptySection { .. } => "There is not any instructions or variables section".to_string(),
+            SicompilerError::NonHexVariable { dir, value, .. } =>
+                format!("The variable dir and value must be a number ({}) '{} {}'", NUMBER_FORMS, dir, value),
+            SicompilerError::NonHexInit { dir, .. } => format!("The init dir must be a number ({}) '{}'", NUMBER_FORMS, dir),
+            SicompilerError::UnknownMnemonic { mnemonic, .. } =>
+                format!("Invalid instruction, '{}' does not appear in the repertoire", mnemonic),
+            SicompilerError::WrongOperandCount { mnemonic, expected, found, .. } =>
+                format!("Invalid number of parameters in '{}', only has {} but get {}", mnemonic, expected, found),
+            SicompilerError::NonHexOperand { mnemonic, operand, .. } =>
+                format!("Invalid parameter '{}' in '{}', the parameters must be a number ({})", operand, mnemonic, NUMBER_FORMS),
+            SicompilerError::NonHexControlWord { word, .. } => format!("The control word '{}' must be in hex base", word),
+            SicompilerError::ControlWordTooWide { word, width, .. } =>
+                format!("The control word '{}' does not fit in {} control signals", word, width),
+            SicompilerError::MalformedMicroprogramLine { .. } =>
+                "Invalid microprogram format, the correct way is <CB WORD>".to_string(),
+            SicompilerError::AddressOutOfRange { address, max, .. } =>
+                format!("The address '{}' is out of the memory, the max is '{:X}'", address, max),
+            SicompilerError::InvalidOpcode { opcode, address } =>
+                format!("The word at '{:X}' has the opcode {}, which is not in the repertoire", address, opcode),
+            SicompilerError::StepLimitExceeded { steps } =>
+                format!("The program did not halt after {} microsteps", steps),
+            SicompilerError::DuplicateSymbol { name, .. } => format!("The symbol '{}' is defined more than once", name),
+            SicompilerError::UndefinedSymbol { name, .. } => format!("The symbol '{}' is not defined", name),
+            SicompilerError::InvalidSymbolName { name, .. } => format!("Invalid symbol name '{}'", name),
+            SicompilerError::MalformedImage { detail, .. } => format!("Invalid memory image, {}", detail),
+            SicompilerError::Unformatted { .. } => "The program is not formatted".to_string(),
+            SicompilerError::OverwriteInput { path, .. } => format!("The output path '{}' is an input file", path),
+            SicompilerError::EmptyRepertoire { .. } => "The repertoire does not declare any instruction".to_string(),
+            SicompilerError::MalformedInstructionLine { .. } =>
+                "Invalid instruction format, the correct way is <MNEMONIC SIGNATURE [WORDS]>".to_string(),
+            SicompilerError::InvalidSignature { signature, .. } =>
+                format!("Invalid operand signature '{}', the correct way is 'true', 'false' or <KIND[:WIDTH],...>", signature),
+            SicompilerError::DuplicateMnemonic { mnemonic, .. } => format!("The mnemonic '{}' is declared more than once", mnemonic),
+            SicompilerError::ExtraRepertoireSeparator { .. } =>
+                "Invalid repertoire structure, the '$' character can only enclose the microprogram section".to_string(),
+            SicompilerError::SignatureTooWide { signature, width, max, .. } =>
+                format!("The operands '{}' take {} bits, but the operand field only has {}", signature, width, max),
+            SicompilerError::WrongOperandKind { mnemonic, operand, expected, .. } =>
+                format!("Invalid parameter '{}' in '{}', the parameter must be of kind {}", operand, mnemonic, expected),
+            SicompilerError::OperandOutOfRange { mnemonic, operand, width, .. } =>
+                format!("The parameter '{}' in '{}' does not fit in {} bits", operand, mnemonic, width),
+            SicompilerError::ValueOutOfRange { value, bits, max, .. } =>
+                format!("The value '{}' does not fit in {} data bits, the max is '{:X}'", value, bits, max),
+            SicompilerError::MalformedHeaderLine { .. } =>
+                "Invalid header format, the correct way is <ADDRESS BITS> or <DATA BITS>".to_string(),
+            SicompilerError::InvalidWidth { name, width, max, .. } =>
+                format!("Invalid {} width '{}', it must be between 1 and {} bits", name, width, max),
+            SicompilerError::InvalidExpression { expression, .. } => format!("Invalid expression '{}'", expression),
+            SicompilerError::ExpressionOverflow { expression, .. } =>
+                format!("The expression '{}' overflows, its value must be between 0 and '{:X}'", expression, u64::MAX),
+            SicompilerError::DivisionByZero { expression, .. } => format!("The expression '{}' divides by zero", expression),
+            SicompilerError::MalformedConstant { .. } =>
+                "Invalid constant format, the correct way is <.equ NAME VALUE> or <NAME = VALUE>".to_string(),
+            SicompilerError::OverlappingAddress { address, .. } =>
+                format!("The address '{}' is already taken by another variable or instruction", address),
+        }
+    }
+
+    /// Describes the repertoire entry an instruction was checked against.
+    fn signature_note(mnemonic: &str, entry: &Span) -> String {
+        match entry.is_unknown() {
+            true => format!("checked against the operand signature of the repertoire entry '{}'", mnemonic),
+            false => format!("checked against the operand signature of the repertoire entry '{}', declared at {}", mnemonic, entry)
+        }
+    }
+
+    fn entry_note(mnemonic: &str, params: usize, entry: &Span) -> String {
+        let params: String = match params {
+            0 => "no parameters".to_string(),
+            1 => "one parameter".to_string(),
+            count => format!("{} parameters", count)
+        };
+
+        let mut note: String = format!("checked against the repertoire entry '{}', which takes {}", mnemonic, params);
+
+        if !entry.is_unknown() {
+            note.push_str(&format!(", declared at {}", entry));
+        }
+
+        note
+    }
+
+    /// Builds the `Diagnostic` used to render the error, with its notes and help.
+    pub fn diagnostic(&self) -> Diagnostic {
+        let span: Span = self.span().cloned().unwrap_or_default();
+        let diagnostic: Diagnostic = Diagnostic::new(&self.message(), span).with_code(self.code());
+
+        match self {
+            SicompilerError::MissingSection { .. } | SicompilerError::TooManySections { .. } =>
+                diagnostic.with_help("a program is written as '<variables> @ <start dir> @ <instructions>'"),
+            SicompilerError::InvalidVariableFormat { .. } =>
+                diagnostic.with_help("write each variable as its dir followed by its value and, optionally, its name, e.g. '0 0003 count'"),
+            SicompilerError::MissingInitAddress { .. } =>
+                diagnostic.with_help("write the address of the first instruction between the two '@'"),
+            SicompilerError::DuplicateInitAddress { first, .. } =>
+                diagnostic.with_note(&format!("the program already starts at '{}'", first)),
+            SicompilerError::MissingMicroprogram { .. } =>
+                diagnostic.with_help("a repertoire starts with the microprogram section between two '$' lines"),
+            SicompilerError::RepertoireTooLarge { .. } =>
+                diagnostic.with_note("this is the first instruction over the limit"),
+            SicompilerError::NonHexVariable { .. } =>
+                diagnostic.with_help("write the dir and the value of the variable as numbers, e.g. '0 0003' or '0x10 d'25'"),
+            SicompilerError::NonHexInit { .. } =>
+                diagnostic.with_help("write the address of the first instruction as a number, e.g. '6' or '0x6'"),
+            SicompilerError::UnknownMnemonic { .. } =>
+                diagnostic.with_help("check the spelling of the mnemonic or add it to the repertoire"),
+            SicompilerError::WrongOperandCount { mnemonic, expected, found, entry, .. } => {
+                let help: &str = if found > expected { "remove the extra parameters" } else { "add the missing parameters" };
+                diagnostic.with_note(&SicompilerError::entry_note(mnemonic, *expected, entry)).with_help(help)
+            },
+            SicompilerError::NonHexOperand { mnemonic, entry, .. } =>
+                diagnostic.with_note(&SicompilerError::entry_note(mnemonic, 1, entry))
+                    .with_help("write the parameter as a number, e.g. '0012' or '0b10010'"),
+            SicompilerError::NonHexControlWord { .. } =>
+                diagnostic.with_help("write the control signals in hex base, e.g. '8000100'"),
+            SicompilerError::ControlWordTooWide { .. } =>
+                diagnostic.with_help("use '--control-width' to change the number of control signals"),
+            SicompilerError::MalformedMicroprogramLine { .. } =>
+                diagnostic.with_help("write each control word of the fetch cycle as 'CB' followed by the word, e.g. 'CB 4000100'"),
+            SicompilerError::AddressOutOfRange { .. } =>
+                diagnostic.with_note("instructions are placed one after another from the init address"),
+            SicompilerError::InvalidOpcode { .. } =>
+                diagnostic.with_help("check that the program jumps to an instruction and not to a variable"),
+            SicompilerError::StepLimitExceeded { .. } =>
+                diagnostic.with_help("check that every path of the program reaches an instruction that halts"),
+            SicompilerError::DuplicateSymbol { first, .. } if !first.is_unknown() =>
+                diagnostic.with_note(&format!("first defined at {}", first)).with_help("rename one of them"),
+            SicompilerError::DuplicateSymbol { .. } => diagnostic.with_help("rename one of them"),
+            SicompilerError::UndefinedSymbol { name, .. } =>
+                diagnostic.with_help(&format!("define it as a label, e.g. '{0}: HALT', or as a variable, e.g. '0 0000 {0}'", name)),
+            SicompilerError::InvalidSymbolName { .. } =>
+                diagnostic.with_help("a name starts with a letter or '_', has only letters, digits and '_', and can not be a hex number"),
+            SicompilerError::MalformedImage { .. } =>
+                diagnostic.with_help("check that '--format' is the format the image was written in"),
+            SicompilerError::Unformatted { .. } =>
+                diagnostic.with_note("this is the first line that changes").with_help("run 'sicompiler fmt' to format it"),
+            SicompilerError::OverwriteInput { .. } =>
+                diagnostic.with_help("write the output to another path with '--out'"),
+            SicompilerError::EmptyRepertoire { .. } =>
+                diagnostic.with_help("declare the instructions after the microprogram section, e.g. 'HALT false 0'"),
+            SicompilerError::MalformedInstructionLine { .. } =>
+                diagnostic.with_help("write the mnemonic, its operand signature and its microcode, e.g. 'ADD true 8000100 1100 28200'"),
+            SicompilerError::InvalidSignature { .. } =>
+                diagnostic.with_help("write 'false' for no operands, 'true' for one address, or each operand as its kind and width, e.g. 'reg:4,imm:8'"),
+            SicompilerError::DuplicateMnemonic { first, .. } if !first.is_unknown() =>
+                diagnostic.with_note(&format!("first declared at {}", first)).with_help("remove or rename one of them"),
+            SicompilerError::DuplicateMnemonic { .. } => diagnostic.with_help("remove or rename one of them"),
+            SicompilerError::ExtraRepertoireSeparator { .. } =>
+                diagnostic.with_help("a repertoire has two '$' lines, one before and one after the microprogram section"),
+            SicompilerError::SignatureTooWide { .. } =>
+                diagnostic.with_help("make the operands narrower, or use fewer of them"),
+            SicompilerError::WrongOperandKind { mnemonic, expected, entry, .. } =>
+                diagnostic.with_note(&SicompilerError::signature_note(mnemonic, entry))
+                    .with_help(if expected == "register" { "write the register as 'R' and its number, e.g. 'R1'" } else { "write the parameter as a number, e.g. '0012' or '0b10010', or as a name" }),
+            SicompilerError::OperandOutOfRange { mnemonic, width, entry, .. } =>
+                diagnostic.with_note(&SicompilerError::signature_note(mnemonic, entry)).with_help(&format!("the largest value is '{:X}'", (1u64 << width) - 1)),
+            SicompilerError::ValueOutOfRange { bits, .. } =>
+                diagnostic.with_help(&format!("write a smaller value, or declare more data bits in the repertoire header, e.g. 'DATA {}'", bits + 1)),
+            SicompilerError::MalformedHeaderLine { .. } =>
+                diagnostic.with_help("the lines before the microprogram section declare the widths of the machine, e.g. 'ADDRESS 12' and 'DATA 16'"),
+            SicompilerError::InvalidWidth { .. } =>
+                diagnostic.with_help("write the number of bits in decimal base"),
+            SicompilerError::InvalidExpression { .. } =>
+                diagnostic.with_help("combine numbers, names and '$' with '+ - * / & | << >>' and parentheses, e.g. 'table+2'"),
+            SicompilerError::ExpressionOverflow { .. } =>
+                diagnostic.with_note("every step of an expression is computed with natural numbers of 64 bits"),
+            SicompilerError::MalformedConstant { .. } =>
+                diagnostic.with_help("write the name of the constant and its value, e.g. '.equ SIZE 10' or 'SIZE = 10'"),
+            SicompilerError::OverlappingAddress { first, .. } if !first.is_unknown() =>
+                diagnostic.with_note(&format!("first placed at {}", first))
+                    .with_help("move the variable to a free dir, or start the instructions at another address"),
+            SicompilerError::OverlappingAddress { .. } =>
+                diagnostic.with_help("move the variable to a free dir, or start the instructions at another address"),
+            _ => diagnostic
+        }
+    }
 }
 
 impl fmt::Display for SicompilerError {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        match self {
-            SicompilerError::Io(err) => write!(f, "I/O error: {}", err),
-            SicompilerError::TokenizationError(msg) => write!(f, "Tokenization error: {}", msg),
-            SicompilerError::ValidationError(msg) => write!(f, "Validation error: {}", msg)
+        if let Some(span) = self.span().filter(|span: &&Span| !span.is_unknown()) {
+            write!(f, "{}: ", span)?;
         }
+
+        write!(f, "error[{}]: {}", self.code(), self.message())
     }
 }
 
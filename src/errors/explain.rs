@@ -0,0 +1,434 @@
+/// Returns the long-form description of an error code, as printed by `--explain`.
+///
+/// ## Arguments
+///
+/// - `code` - The code of the error, e.g. `SC0013`.
+///
+/// ## Returns
+///
+/// The description, or `None` if the code does not exist.
+pub fn explain(code: &str) -> Option<&'static str> {
+    let explanation: &str = match code.to_uppercase().as_str() {
+        "SC0001" => "\
+The compiler could not read or write a file.
+
+Check that the input and repertoire paths exist and can be read, and that the
+output path can be written.",
+        "SC0002" => "\
+The program file is empty.
+
+A program must have, at least, a variables section, a start address and an
+instructions section.",
+        "SC0003" => "\
+The program has less than three sections.
+
+A program is divided into three sections separated by the '@' character:
+
+    <variables declaration>
+    @
+    <start dir>
+    @
+    <sentences>
+
+This error usually means one of the '@' lines is missing.",
+        "SC0004" => "\
+The program has more than three sections.
+
+A program is divided into three sections separated by the '@' character:
+
+    <variables declaration>
+    @
+    <start dir>
+    @
+    <sentences>
+
+The '@' character can only be used to separate sections, so it can't appear
+anywhere else in the program outside of a comment.",
+        "SC0005" => "\
+A line of the variables section is not written as '<DIR VALUE [NAME]>'.
+
+Each variable is declared in its own line with the address where it is stored
+followed by its initial value and, optionally, a name the instructions can
+use instead of the address:
+
+    0 0003 count
+    1 0003",
+        "SC0006" => "\
+The init section does not have an address.
+
+The section between the two '@' lines holds the address of the first
+instruction of the program:
+
+    @
+    6
+    @",
+        "SC0007" => "\
+The init section has more than one address.
+
+The program can only start at one address, so the section between the two
+'@' lines must hold a single value.",
+        "SC0008" => "\
+The repertoire does not have a microprogram section.
+
+A repertoire starts with the microprogram section enclosed by two '$' lines,
+followed by the valid instructions:
+
+    $
+    <microprogram section>
+    $
+    <valid instructions>",
+        "SC0009" => "\
+The repertoire has more instructions than the machine can encode.
+
+The opcode of an instruction has 5 bits, so a repertoire can have at most 32
+instructions.",
+        "SC0010" => "\
+The program does not have any variables or instructions.
+
+Both the variables section and the instructions section must have at least one
+line.",
+        "SC0011" => "\
+The dir or the value of a variable is not a number.
+
+Both numbers of a variable must be written in hexadecimal, or with the marker
+of another base:
+
+    0 0003   # correct
+    0 d'3    # correct, decimal
+    0 three  # incorrect
+
+A number can be written as '0x1F' in hex, '0b1010' in binary, '0o17' in octal,
+'d'25' or '25d' in decimal, or as a character between quotes, like 'A'.",
+        "SC0012" => "\
+The init address is not a number.
+
+The address of the first instruction must be written in hexadecimal, or with
+the marker of another base, like '0x6', '0b110' or '6d'.",
+        "SC0013" => "\
+The mnemonic of an instruction does not appear in the repertoire.
+
+Only the instructions declared in the repertoire file passed with '--rep' can
+be used in the program. Check the spelling of the mnemonic (mnemonics are case
+sensitive) or add the instruction to the repertoire.",
+        "SC0014" => "\
+An instruction has a different number of parameters than its repertoire entry.
+
+The second column of a repertoire entry tells if the instruction takes a
+parameter:
+
+    HALT false 0
+    ADD true 8000100 1100 28200
+
+With this repertoire 'HALT' must be written alone and 'ADD' must be followed by
+one address, e.g. 'ADD 0001'.",
+        "SC0015" => "\
+A parameter of an instruction is not a number.
+
+The parameters of the instructions are addresses, and must be written in
+hexadecimal, with the marker of another base, or as a label or a variable name:
+
+    JMP 12    # correct
+    JMP 18d   # correct, decimal
+    JMP XYZ   # incorrect",
+        "SC0016" => "\
+A control word of the repertoire is not in hex base.
+
+The control words of the microprogram section and the microsteps of the
+instructions set one control signal per bit, and must be written in
+hexadecimal:
+
+    CB 4000100
+    ADD true 8000100 1100 28200",
+        "SC0017" => "\
+A control word of the repertoire has more bits than the control signals.
+
+By default the machine has 28 control signals, so the largest control word is
+'FFFFFFF'. Use '--control-width' to change the number of control signals.",
+        "SC0018" => "\
+A line of the microprogram section is not written as 'CB <WORD>'.
+
+The microprogram section holds the control words of the fetch cycle, one per
+line, each one after the 'CB' (control bus) prefix:
+
+    $
+    CB 4000100
+    CB 0201100
+    CB 3000300
+    $",
+        "SC0019" => "\
+A variable or an instruction is placed outside the memory of the machine.
+
+The memory has 4096 cells, so every address must be between '0' and 'FFF',
+unless the repertoire declares other address bits in its header. Variables
+are placed at their dir, and instructions one after another from the init
+address, so a long program that starts near the end of the memory also
+overflows it.",
+        "SC0020" => "\
+The simulator fetched a word whose opcode is not in the repertoire.
+
+Every instruction is stored as its opcode, its position in the repertoire,
+followed by its operand. This error usually means the program jumped to, or
+ran into, a variable, so its value was decoded as an instruction.",
+        "SC0021" => "\
+The simulated program ran more microsteps than allowed without halting.
+
+The simulator stops a program after a fixed number of microsteps so an
+infinite loop does not hang it. Check that every path of the program reaches
+an instruction whose microcode halts the machine, like 'HALT'.",
+        "SC0022" => "\
+A label or a variable name is defined more than once.
+
+Labels and variables share their names, and every name stands for one
+address, so the same name can not be given to two instructions or variables:
+
+    0 0003 count
+    @
+    6
+    @
+    count: ADD count   # incorrect, 'count' is already a variable",
+        "SC0023" => "\
+An operand names a label or a variable that is not defined.
+
+Labels are defined in the instructions section writing their name followed
+by ':' before an instruction, or in the line above it. Variables are named
+writing the name after their initial value:
+
+    0 0003 count
+    @
+    6
+    @
+    LOOP: ADD count
+    SFZ
+    JMP LOOP
+
+Check the spelling of the name, names are case sensitive.",
+        "SC0024" => "\
+A label or a variable name can not be used as a name.
+
+A name starts with a letter or '_' and has only letters, digits and '_'.
+It can not be a valid hex number either, like 'FACE' or 'ADD', because an
+operand with that name would be ambiguous.",
+        "SC0025" => "\
+A memory image can not be read in the format it was given in.
+
+The disassembler reads the image in the format given with '--format':
+
+    bin       3 bytes per word in big-endian order, from address 0
+    logisim   a 'v2.0 raw' file with hex words, where 'N*WORD' repeats a word
+    digital   a hex word per line
+
+An image can not have more words than the memory, 4096.",
+        "SC0026" => "\
+A program checked with 'sicompiler fmt --check' is not formatted.
+
+In a formatted program the tokens are separated by one space, with no
+indentation, the hex numbers are in uppercase, the one-line comments are two
+spaces after the code and there are no consecutive blank lines:
+
+    0 0003 count  #Counter
+    @
+    6
+    @
+    LOOP: ADD count
+
+Run 'sicompiler fmt' without '--check' to rewrite the file formatted.",
+        "SC0027" => "\
+An output would overwrite one of the files that were read.
+
+The output path, the path of the listing or the path of the control ROM image
+points to the program or the repertoire, so writing it would lose the source:
+
+    sicompiler build --rep program.rep --out program.txt program.txt
+
+Choose another path with '--out', or leave the default 'out.txt'.",
+        "SC0028" => "\
+The repertoire does not declare any instruction.
+
+The valid instructions are declared one per line after the microprogram
+section, so a program can not use any instruction if there are none:
+
+    $
+    CB 4000100
+    $
+    HALT false 0",
+        "SC0029" => "\
+A line of the instructions section of the repertoire is not written as
+'<MNEMONIC SIGNATURE [WORDS]>'.
+
+Each instruction is declared with its mnemonic, the signature of its operands
+and the control words of its microcode:
+
+    HALT false 0
+    ADD true 8000100 1100 28200
+
+This error usually means the signature is missing.",
+        "SC0030" => "\
+The operand signature of a repertoire instruction is not valid.
+
+The second column of an instruction declares its operands. It is 'false' for
+an instruction without operands, 'true' for one 12-bit address, or the list of
+its operands separated by ',', each one its kind and, optionally, its width in
+bits after ':':
+
+    HALT false 0
+    ADD true 8000100 1100 28200
+    LDI reg:4,imm:8 8000100 1100 28200
+
+The kinds are 'addr' (or 'address'), 'imm' (or 'immediate') and 'reg' (or
+'register'). Addresses and immediates are 12 bits wide by default, and
+registers 4 bits.",
+        "SC0031" => "\
+A mnemonic is declared more than once in the repertoire.
+
+The program names the instructions by their mnemonic, so two instructions with
+the same mnemonic can not be told apart, and the second one could never be
+used. Remove one of them or give it another mnemonic.",
+        "SC0032" => "\
+The repertoire has more than two '$' separators.
+
+The '$' character encloses the microprogram section, so it can only appear in
+the line before and in the line after it:
+
+    $
+    <microprogram section>
+    $
+    <valid instructions>",
+        "SC0033" => "\
+The operands of a repertoire instruction take more bits than the operand field.
+
+The operands of an instruction are packed, the first one in the highest bits,
+in the 12-bit operand field of the encoded word, after its 5-bit opcode, so
+their widths can add up to 12 at most:
+
+    LDI reg:4,imm:8 8000100 1100 28200    # correct, 12 bits
+    LDI reg:4,imm:12 8000100 1100 28200   # incorrect, 16 bits",
+        "SC0034" => "\
+An operand of an instruction is not of the kind its repertoire entry declares.
+
+Addresses and immediates are written as numbers, in any of the accepted bases,
+or as a label or a variable name, and registers as 'R' followed by their number in decimal:
+
+    LDI reg:4,imm:8 8000100 1100 28200
+
+With this repertoire 'LDI R1 2A' is correct, but 'LDI 1 2A' and 'LDI R1 R2'
+are not.",
+        "SC0035" => "\
+An operand of an instruction does not fit in the bits its repertoire entry
+declares.
+
+Each operand is encoded in the number of bits of its signature, so an 8-bit
+immediate can be '0' to 'FF' and a 4-bit register 'R0' to 'R15'. Labels and
+variables used as operands must also have an address that fits.",
+        "SC0036" => "\
+The initial value of a variable does not fit in the data words of the machine.
+
+The data words have 16 bits by default, so a value can be '0' to 'FFFF':
+
+    0 FFFF    # correct
+    0 10000   # incorrect
+
+A repertoire can declare wider data words in its header, e.g. 'DATA 24'.",
+        "SC0037" => "\
+A line of the repertoire header is not written as '<ADDRESS BITS>' or
+'<DATA BITS>'.
+
+The lines before the microprogram section can declare the widths of the
+machine the repertoire runs on, in decimal base:
+
+    ADDRESS 10
+    DATA 16
+    $
+    <microprogram section>
+    $
+    <valid instructions>
+
+The widths not declared keep their default, 12 address bits and 16 data bits.",
+        "SC0038" => "\
+A width of the machine is not a valid number of bits.
+
+The address bits give the size of the memory and of the operand field of the
+instructions, and can be 1 to 14, so the byte address of every memory cell
+fits in the Intel HEX and S-record formats. The data bits give the width of
+the data words and of ACC, and can be 1 to 32.",
+        "SC0039" => "\
+An operand or a data value is not a well written expression.
+
+An expression combines numbers, label and variable names, and '$', the
+address of the instruction or the variable it is written in, with the
+operators '+ - * / & | << >>' and parentheses:
+
+    ADD table+2      # correct
+    JMP $-3          # correct
+    ADD (count+1)*2  # correct
+    ADD table+       # incorrect, the operator has no right side
+    ADD (count+1     # incorrect, the parenthesis is not closed
+
+The operators have the precedence they have in C, '* /' first, then '+ -',
+'<< >>', '&' and '|' last.",
+        "SC0040" => "\
+An expression overflows.
+
+Expressions are computed at assembly time with natural numbers of 64 bits, so
+no step can have a negative value or a value larger than 'FFFFFFFFFFFFFFFF':
+
+    JMP $-3   # incorrect at an address lower than 3
+
+The value of the expression must also fit in the operand or the data word it
+is written in, which is reported as 'SC0035' or 'SC0036'.",
+        "SC0041" => "\
+An expression divides by zero.
+
+The right side of '/' is zero, maybe because it is an expression whose value
+is zero, like 'count/(1-1)'.",
+        "SC0042" => "\
+A constant is not defined as '.equ NAME VALUE' or 'NAME = VALUE'.
+
+A constant gives a name to a value without taking any memory, so the same
+number can be changed in one place. It is defined in its own line, in the
+variables or the instructions section, before the constants that use it:
+
+    .equ TABLE 20
+    SIZE = TABLE + 4
+    @
+    6
+    @
+    ADD SIZE
+
+This error usually means the name or the value is missing.",
+        "SC0043" => "\
+A variable or an instruction is placed at an address another one already takes.
+
+Every cell of the memory holds one word, so two variables can not share a dir
+and the instructions, placed one after another from the start dir, can not
+run over a variable:
+
+    7 0005 x
+    @
+    6
+    @
+    ADD x
+    HALT
+
+Here 'HALT' is placed at '7', the dir of 'x'. Move the variable to a free dir,
+or start the instructions at another address.",
+        _ => return None
+    };
+
+    Some(explanation)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_explain() {
+        for number in 1..=43 {
+            assert!(explain(&format!("SC{:04}", number)).is_some());
+        }
+
+        assert!(explain("sc0013").unwrap().starts_with("The mnemonic of an instruction"));
+        assert!(explain("SC9999").is_none());
+        assert!(explain("").is_none());
+    }
+}
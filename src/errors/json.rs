@@ -0,0 +1,113 @@
+use crate::models::span::Span;
+use super::{diagnostic::Diagnostic, diagnostics::Diagnostics};
+
+/// Escapes a string and wraps it in quotes, as a JSON string literal.
+fn string(text: &str) -> String {
+    let mut escaped: String = String::from('"');
+
+    for c in text.chars() {
+        match c {
+            '"' => escaped.push_str("\\\""),
+            '\\' => escaped.push_str("\\\\"),
+            '\n' => escaped.push_str("\\n"),
+            '\r' => escaped.push_str("\\r"),
+            '\t' => escaped.push_str("\\t"),
+            c if (c as u32) < 0x20 => escaped.push_str(&format!("\\u{:04x}", c as u32)),
+            c => escaped.push(c)
+        }
+    }
+
+    escaped.push('"');
+    escaped
+}
+
+/// Converts an optional string into a JSON string or `null`.
+fn optional_string(text: Option<&str>) -> String {
+    text.map_or("null".to_string(), string)
+}
+
+/// Converts a span into a JSON object with its start and end, or `null` if it does not point to a line.
+fn span(span: &Span) -> String {
+    if span.line() == 0 {
+        return "null".to_string();
+    }
+
+    format!(
+        "{{\"line\":{},\"column\":{},\"end_line\":{},\"end_column\":{}}}",
+        span.line(),
+        span.column(),
+        span.line(),
+        span.column() + span.len()
+    )
+}
+
+/// Converts a diagnostic into a single-line JSON object.
+///
+/// ## Arguments
+///
+/// - `diagnostic` - The diagnostic to convert.
+///
+/// ## Returns
+///
+/// An object with the `code`, `severity`, `message`, `file`, `span`, `notes` and `suggested_fix` of the diagnostic.
+pub fn to_json(diagnostic: &Diagnostic) -> String {
+    let file: Option<&str> = Some(diagnostic.span().file()).filter(|file: &&str| !file.is_empty());
+    let notes: Vec<String> = diagnostic.notes().iter().map(|note: &String| string(note)).collect();
+
+    format!(
+        "{{\"code\":{},\"severity\":{},\"message\":{},\"file\":{},\"span\":{},\"notes\":[{}],\"suggested_fix\":{}}}",
+        optional_string(diagnostic.code()),
+        string(&diagnostic.severity().to_string()),
+        string(diagnostic.message()),
+        optional_string(file),
+        span(diagnostic.span()),
+        notes.join(","),
+        optional_string(diagnostic.help())
+    )
+}
+
+/// Converts every collected diagnostic into JSON, one object per line.
+///
+/// ## Arguments
+///
+/// - `diagnostics` - The collected diagnostics.
+///
+/// ## Returns
+///
+/// The JSON objects, each one followed by a line break.
+pub fn render_all(diagnostics: &Diagnostics) -> String {
+    diagnostics.errors()
+        .iter()
+        .map(|error| format!("{}\n", to_json(&error.diagnostic())))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_to_json() {
+        let diagnostic: Diagnostic = Diagnostic::new("Invalid parameter 'XYZ' in \"JMP\"", Span::new("dir\\program.txt", 5, 5, 3))
+            .with_code("SC0015")
+            .with_note("checked against the repertoire entry 'JMP'")
+            .with_help("write the parameter in hex base");
+
+        assert_eq!(
+            to_json(&diagnostic),
+            "{\"code\":\"SC0015\",\"severity\":\"error\",\"message\":\"Invalid parameter 'XYZ' in \\\"JMP\\\"\",\
+\"file\":\"dir\\\\program.txt\",\"span\":{\"line\":5,\"column\":5,\"end_line\":5,\"end_column\":8},\
+\"notes\":[\"checked against the repertoire entry 'JMP'\"],\"suggested_fix\":\"write the parameter in hex base\"}"
+        );
+    }
+
+    #[test]
+    fn test_to_json_without_span() {
+        let diagnostic: Diagnostic = Diagnostic::new("I/O error:\tmissing", Span::default());
+
+        assert_eq!(
+            to_json(&diagnostic),
+            "{\"code\":null,\"severity\":\"error\",\"message\":\"I/O error:\\tmissing\",\"file\":null,\"span\":null,\"notes\":[],\"suggested_fix\":null}"
+        );
+    }
+}
@@ -1 +1,6 @@
-pub mod error;
\ No newline at end of file
+pub mod error;
+pub mod diagnostics;
+pub mod diagnostic;
+pub mod renderer;
+pub mod explain;
+pub mod json;
\ No newline at end of file
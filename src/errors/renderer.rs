@@ -0,0 +1,175 @@
+use crate::models::{source::SourceFile, span::Span};
+use super::{diagnostic::{Diagnostic, Severity}, diagnostics::Diagnostics};
+
+const RED: &str = "\x1b[1;31m";
+const YELLOW: &str = "\x1b[1;33m";
+const BLUE: &str = "\x1b[1;34m";
+const BOLD: &str = "\x1b[1m";
+const RESET: &str = "\x1b[0m";
+
+/// The `Renderer` struct is responsible for displaying diagnostics the way rustc does:
+/// the primary message, the offending source line with the token underlined, and the notes and help.
+pub struct Renderer {
+    color: bool,
+}
+
+impl Renderer {
+    /// Wraps `text` in an ANSI style if colours are enabled.
+    fn paint(&self, style: &str, text: &str) -> String {
+        if self.color { format!("{}{}{}", style, text, RESET) } else { text.to_string() }
+    }
+
+    /// Expands the tabs of a source line so the underline stays aligned.
+    fn expand_tabs(text: &str) -> String {
+        text.replace('\t', "    ")
+    }
+
+    /// Renders the source line a span points to, with its token underlined.
+    /// 
+    /// ## Arguments
+    /// 
+    /// - `span` - The location of the token.
+    /// - `source` - The file the span points into.
+    /// 
+    /// ## Returns
+    /// 
+    /// The annotated snippet, or `None` if the line is not part of the file.
+    /// 
+    fn render_snippet(&self, span: &Span, source: &SourceFile) -> Option<String> {
+        let line: &str = source.line(span.line())?;
+        let line_number: String = span.line().to_string();
+        let gutter: String = " ".repeat(line_number.len());
+
+        let prefix: String = line.chars().take(span.column().saturating_sub(1)).collect();
+        let token: String = line.chars().skip(span.column().saturating_sub(1)).take(span.len()).collect();
+        let padding: String = " ".repeat(Renderer::expand_tabs(&prefix).chars().count());
+        let underline: String = "^".repeat(Renderer::expand_tabs(&token).chars().count().max(1));
+
+        let mut snippet: String = String::new();
+        snippet.push_str(&format!("{} {}\n", gutter, self.paint(BLUE, "|")));
+        snippet.push_str(&format!("{} {} {}\n", self.paint(BLUE, &line_number), self.paint(BLUE, "|"), Renderer::expand_tabs(line)));
+        snippet.push_str(&format!("{} {} {}{}\n", gutter, self.paint(BLUE, "|"), padding, self.paint(RED, &underline)));
+
+        Some(snippet)
+    }
+
+    /// Creates a new `Renderer`.
+    ///
+    /// ## Arguments
+    ///
+    /// - `color` - Whether the output is coloured with ANSI escape codes.
+    pub fn new(color: bool) -> Renderer {
+        Renderer { color }
+    }
+
+    /// Renders a single diagnostic.
+    /// 
+    /// ## Arguments
+    /// 
+    /// - `diagnostic` - The diagnostic to render.
+    /// - `source` - The file the diagnostic points into, if it is available.
+    /// 
+    /// ## Returns
+    /// 
+    /// The rendered diagnostic, ending with a line break.
+    /// 
+    pub fn render(&self, diagnostic: &Diagnostic, source: Option<&SourceFile>) -> String {
+        let span: &Span = diagnostic.span();
+        let gutter: String = " ".repeat(span.line().to_string().len());
+
+        let header: String = match diagnostic.code() {
+            Some(code) => format!("{}[{}]", diagnostic.severity(), code),
+            None => diagnostic.severity().to_string()
+        };
+
+        let style: &str = if diagnostic.severity() == Severity::Error { RED } else { YELLOW };
+        let mut output: String = format!("{}{}\n", self.paint(style, &header), self.paint(BOLD, &format!(": {}", diagnostic.message())));
+
+        if !span.is_unknown() {
+            output.push_str(&format!("{}{} {}\n", gutter, self.paint(BLUE, "-->"), span));
+        }
+
+        let snippet: Option<String> = source
+            .filter(|_| span.line() > 0)
+            .and_then(|source: &SourceFile| self.render_snippet(span, source));
+
+        if let Some(snippet) = &snippet {
+            output.push_str(snippet);
+        }
+
+        let has_footer: bool = !diagnostic.notes().is_empty() || diagnostic.help().is_some();
+
+        if snippet.is_some() && has_footer {
+            output.push_str(&format!("{} {}\n", gutter, self.paint(BLUE, "|")));
+        }
+
+        for note in diagnostic.notes() {
+            output.push_str(&format!("{} {} {}: {}\n", gutter, self.paint(BLUE, "="), self.paint(BOLD, "note"), note));
+        }
+
+        if let Some(help) = diagnostic.help() {
+            output.push_str(&format!("{} {} {}: {}\n", gutter, self.paint(BLUE, "="), self.paint(BOLD, "help"), help));
+        }
+
+        output
+    }
+
+    /// Renders every diagnostic collected, followed by a summary with the number of errors.
+    /// 
+    /// ## Arguments
+    /// 
+    /// - `diagnostics` - The collected diagnostics and the files they point into.
+    /// 
+    /// ## Returns
+    /// 
+    /// The rendered diagnostics, separated by blank lines.
+    /// 
+    pub fn render_all(&self, diagnostics: &Diagnostics) -> String {
+        let mut output: String = String::new();
+
+        for error in diagnostics.errors() {
+            let diagnostic: Diagnostic = error.diagnostic();
+            let source: Option<&SourceFile> = diagnostics.source(diagnostic.span().file());
+
+            output.push_str(&self.render(&diagnostic, source));
+            output.push('\n');
+        }
+
+        output.push_str(&format!("{}\n", self.paint(RED, &diagnostics.summary())));
+
+        output
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render() {
+        let source: SourceFile = SourceFile::new("program.txt", "0 0003\n@\n6\n@\nJMP XYZ #Loop\n");
+        let diagnostic: Diagnostic = Diagnostic::new("Invalid parameters in 'JMP', the parameters must be in hex base", Span::new("program.txt", 5, 5, 3))
+            .with_note("checked against the repertoire entry 'JMP'")
+            .with_help("write the parameter in hex base");
+
+        let expected: &str = "error: Invalid parameters in 'JMP', the parameters must be in hex base
+ --> program.txt:5:5
+  |
+5 | JMP XYZ #Loop
+  |     ^^^
+  |
+  = note: checked against the repertoire entry 'JMP'
+  = help: write the parameter in hex base
+";
+
+        assert_eq!(Renderer::new(false).render(&diagnostic, Some(&source)), expected);
+    }
+
+    #[test]
+    fn test_render_without_source() {
+        let diagnostic: Diagnostic = Diagnostic::new("The file is empty", Span::file_only("program.txt"));
+
+        assert_eq!(Renderer::new(false).render(&diagnostic, None), "error: The file is empty\n --> program.txt\n");
+        assert!(Renderer::new(true).render(&diagnostic, None).contains("\x1b[1;31merror\x1b[0m"));
+    }
+}
@@ -1,33 +1,427 @@
+// Errors carry the spans and values needed to render them, so they are bigger than clippy's default threshold.
+#![allow(clippy::result_large_err)]
+
 pub mod models;
 pub mod structs;
 pub mod errors;
 
-use std::collections::HashMap;
+use std::io::{BufRead, Write};
+
+use structs::{
+    tokenizer::Tokenizer,
+    literal::Literal,
+    validator::Validator,
+    simulator::Simulator,
+    debugger::Debugger,
+    assembler::Assembler,
+    disassembler::Disassembler,
+    formatter::Formatter,
+    emitter::Emitter,
+    tokenizer::DEFAULT_CONTROL_WIDTH,
+    files
+};
+use models::{
+    program::Program,
+    repertoire::Repertoire,
+    instruction::Instruction,
+    operand::Operand,
+    microprogram::ControlWord,
+    image::MemoryImage,
+    source::SourceFile,
+    span::Span,
+    args::{OutputFormat, SourceArgs, CheckArgs, BuildArgs, RunArgs, FmtArgs, RepertoireArgs, DebugArgs, DisasmArgs}
+};
+use errors::{diagnostics::Diagnostics, error::SicompilerError};
+
+/// The name the errors of a program parsed with `parse_program` point to.
+pub const PROGRAM_NAME: &str = "<program>";
+
+/// The name the errors of a repertoire parsed with `parse_repertoire` point to.
+pub const REPERTOIRE_NAME: &str = "<repertoire>";
+
+/// Parses the source of a program, without reading any file. Its errors point to `PROGRAM_NAME`.
+///
+/// ## Arguments
+///
+/// - `source` - The source of the program.
+///
+/// ## Returns
+///
+/// The program, or every error found. The diagnostics hold the source, so they can be rendered
+/// with its lines.
+///
+pub fn parse_program(source: &str) -> Result<Program, Diagnostics> {
+    parse_named_program(source, PROGRAM_NAME)
+}
+
+/// Parses the source of a program, without reading any file, with the name its errors point to.
+///
+/// ## Arguments
+///
+/// - `source` - The source of the program.
+/// - `name` - The name of the program, e.g. the path it was read from.
+///
+/// ## Returns
+///
+/// The program, or every error found. The diagnostics hold the source, so they can be rendered
+/// with its lines.
+///
+pub fn parse_named_program(source: &str, name: &str) -> Result<Program, Diagnostics> {
+    let mut diagnostics: Diagnostics = Diagnostics::new();
+    let source: SourceFile = SourceFile::new(name, source);
+    let result: Result<Program, SicompilerError> = Tokenizer::tokenize_source(&source, &mut diagnostics);
+
+    diagnostics.add_source(source);
+
+    match result {
+        Ok(program) if diagnostics.is_empty() => Ok(program),
+        Ok(_) => Err(diagnostics),
+        Err(err) => {
+            diagnostics.push(err);
+            Err(diagnostics)
+        }
+    }
+}
+
+/// Parses a repertoire, without reading any file, checking that its control words fit in the
+/// default number of control signals.
+///
+/// ## Arguments
+///
+/// - `source` - The content of the repertoire.
+///
+/// ## Returns
+///
+/// The repertoire, or every error found.
+///
+pub fn parse_repertoire(source: &str) -> Result<Repertoire, Diagnostics> {
+    let mut diagnostics: Diagnostics = Diagnostics::new();
+    let source: SourceFile = SourceFile::new(REPERTOIRE_NAME, source);
+    let result: Result<Repertoire, SicompilerError> = Tokenizer::tokenize_repertoire_source(&source, DEFAULT_CONTROL_WIDTH, None, None, &mut diagnostics);
+
+    diagnostics.add_source(source);
+
+    match result {
+        Ok(repertoire) if diagnostics.is_empty() => Ok(repertoire),
+        Ok(_) => Err(diagnostics),
+        Err(err) => {
+            diagnostics.push(err);
+            Err(diagnostics)
+        }
+    }
+}
+
+/// Validates a program against a repertoire, without writing anything.
+///
+/// ## Arguments
+///
+/// - `program` - The program, e.g. returned by `parse_named_program`, whose name the errors point to.
+/// - `repertoire` - The valid instructions, e.g. returned by `parse_repertoire`.
+///
+/// ## Returns
+///
+/// Every problem found, empty if the program is valid.
+///
+pub fn validate(program: &Program, repertoire: &Repertoire) -> Diagnostics {
+    let mut diagnostics: Diagnostics = Diagnostics::new();
+
+    Validator::check_program(program, repertoire, &mut diagnostics);
+
+    diagnostics
+}
+
+/// Encodes a validated program in an output format, without writing any file.
+///
+/// ## Arguments
+///
+/// - `program` - The validated program.
+/// - `repertoire` - The repertoire the program was validated against, which gives the opcodes.
+/// - `format` - The output format.
+///
+/// ## Returns
+///
+/// The encoded program, or the error found if it does not fit in memory.
+///
+pub fn emit(program: &Program, repertoire: &Repertoire, format: OutputFormat) -> Result<Vec<u8>, Diagnostics> {
+    Ok(Emitter::emit(program, repertoire, format)?)
+}
+
+/// Tokenizes the repertoire and the program, pushing every problem found to `diagnostics`.
+///
+/// ## Arguments
+///
+/// - `args` - The paths of the program and the repertoire, and the number of control signals.
+/// - `diagnostics` - The collector where every problem is reported.
+///
+/// ## Returns
+///
+/// The repertoire and the program, or `None` if any of them could not be tokenized.
+///
+fn tokenize(args: &SourceArgs, diagnostics: &mut Diagnostics) -> Option<(Repertoire, Program)> {
+    let mut tokenizer: Tokenizer = Tokenizer::new(&args.input_path, &args.repertoire_path);
+    tokenizer.set_control_width(args.machine.control_width);
+    tokenizer.set_widths(args.machine.address_bits, args.machine.data_bits);
+
+    let repertoire: Repertoire = tokenizer.tokenize_repertoire(diagnostics)
+        .map_err(|err: SicompilerError| diagnostics.push(err))
+        .ok()?;
+
+    let tokens: Program = tokenizer.tokenize(diagnostics)
+        .map_err(|err: SicompilerError| diagnostics.push(err))
+        .ok()?;
+
+    Some((repertoire, tokens))
+}
+
+/// Tokenizes and validates a program without writing anything.
+///
+/// ## Arguments
+///
+/// - `args` - The paths of the program and the repertoire, and the number of control signals.
+///
+/// ## Returns
+///
+/// The validated repertoire and program, or every error found.
+///
+fn load(args: &SourceArgs) -> Result<(Repertoire, Program), Diagnostics> {
+    let mut diagnostics: Diagnostics = Diagnostics::new();
+
+    let Some((repertoire, tokens)) = tokenize(args, &mut diagnostics) else {
+        return Err(diagnostics);
+    };
+
+    Validator::check_program(&tokens, &repertoire, &mut diagnostics);
+
+    diagnostics.into_result().map(|_| (repertoire, tokens))
+}
+
+/// Validates a program, collecting every tokenization and validation problem, without writing any file.
+///
+/// ## Arguments
+///
+/// - `args` - A reference to the arguments of the `check` subcommand.
+///
+/// ## Returns
+///
+/// Returns a `Result` indicating success (`Ok(())`) or every error found (`Err(Diagnostics)`).
+///
+pub fn check(args: &CheckArgs) -> Result<(), Diagnostics> {
+    load(&args.source).map(|_| ())
+}
+
+/// Validates a program and writes it to the output path in the chosen format.
+///
+/// Every tokenization and validation problem found is collected before returning.
+///
+/// ## Arguments
+///
+/// - `args` - A reference to the arguments of the `build` subcommand.
+///
+/// ## Returns
+///
+/// Returns a `Result` indicating success (`Ok(())`) or every error found (`Err(Diagnostics)`).
+///
+pub fn build(args: &BuildArgs) -> Result<(), Diagnostics> {
+    let mut diagnostics: Diagnostics = Diagnostics::new();
+
+    let Some((repertoire, tokens)) = tokenize(&args.source, &mut diagnostics) else {
+        return Err(diagnostics);
+    };
+
+    let mut validator: Validator = Validator::new(tokens, &args.output_path);
+    validator.set_format(args.format);
+    validator.set_control_width(args.source.machine.control_width);
+    validator.set_listing_path(args.listing_path.clone());
+
+    validator.validate(&repertoire, &mut diagnostics);
+
+    diagnostics.into_result()
+}
+
+/// Validates a program and runs it in the simulator until it halts, then writes the registers and
+/// the value of every variable.
+///
+/// ## Arguments
+///
+/// - `args` - A reference to the arguments of the `run` subcommand.
+/// - `output` - Where the state of the machine is written.
+///
+/// ## Returns
+///
+/// Returns a `Result` indicating success (`Ok(())`) or every error found (`Err(Diagnostics)`).
+///
+pub fn run(args: &RunArgs, mut output: impl Write) -> Result<(), Diagnostics> {
+    let (repertoire, tokens): (Repertoire, Program) = load(&args.source)?;
+
+    let mut simulator: Simulator = Simulator::new(&tokens, &repertoire)?;
+    simulator.set_max_steps(args.max_steps);
+    simulator.run()?;
+
+    writeln!(output, "Program halted at {:03X}", simulator.address())?;
+    writeln!(output, "{}", simulator.registers())?;
+
+    for variable in tokens.variables() {
+        let Some(address) = Literal::parse(variable.dir()) else { continue };
+
+        match variable.symbol() {
+            Some(name) => writeln!(output, "{:03X}: {:05X}  {}", address, simulator.memory()[address as usize], name)?,
+            None => writeln!(output, "{:03X}: {:05X}", address, simulator.memory()[address as usize])?
+        }
+    }
+
+    Ok(())
+}
+
+/// Formats the source of a program, rewriting the file, or checks that it is already formatted.
+///
+/// ## Arguments
+///
+/// - `args` - A reference to the arguments of the `fmt` subcommand.
+///
+/// ## Returns
+///
+/// Returns a `Result` indicating success (`Ok(())`) or the error found (`Err(Diagnostics)`),
+/// which with `--check` includes a program that is not formatted.
+///
+pub fn fmt(args: &FmtArgs) -> Result<(), Diagnostics> {
+    let mut diagnostics: Diagnostics = Diagnostics::new();
+
+    let content: String = files::read_to_string(&args.input_path)?;
+    let formatted: String = Formatter::format(&content);
+
+    //* A program read from the standard input is always written back, so fmt can filter it
+    if !args.check && args.input_path == files::STDIO_PATH {
+        files::write(files::STDIO_PATH, formatted.as_bytes())?;
+        return Ok(());
+    }
+
+    if formatted == content {
+        return Ok(());
+    }
+
+    if !args.check {
+        files::write(&args.input_path, formatted.as_bytes())?;
+        return Ok(());
+    }
 
-use structs::{tokenizer::Tokenizer, validator::Validator};
-use models::{program::Program, instruction::Instruction, args::Cli};
-use errors::error::SicompilerError;
+    let line: usize = content.lines().zip(formatted.lines())
+        .position(|(original, formatted): (&str, &str)| original != formatted)
+        .unwrap_or(content.lines().count().min(formatted.lines().count()));
 
-/// Executes the main functionality of the program based on the provided command-line arguments.
+    let source: SourceFile = SourceFile::new(files::display_name(&args.input_path), &content);
+    let len: usize = source.line(line + 1).map_or(0, |text: &str| text.chars().count());
+
+    diagnostics.push(SicompilerError::Unformatted { span: Span::new(source.path(), line + 1, 1, len) });
+    diagnostics.add_source(source);
+
+    Err(diagnostics)
+}
+
+/// Writes the fetch cycle of a repertoire and every instruction with its opcode and its microcode.
 ///
 /// ## Arguments
 ///
-/// - `cli` - A reference to the `Cli` struct containing command-line arguments.
+/// - `args` - A reference to the arguments of the `repertoire` subcommand.
+/// - `output` - Where the repertoire is written.
 ///
 /// ## Returns
 ///
-/// Returns a `Result` indicating success (`Ok(())`) or an error (`Err(SicompilerError)`).
+/// Returns a `Result` indicating success (`Ok(())`) or every error found (`Err(Diagnostics)`).
 ///
-pub fn run(cli: &Cli) -> Result<(), SicompilerError> {
-    let tokenizer: Tokenizer = Tokenizer::new(&cli.input_path, &cli.repertoire_path);
+pub fn repertoire(args: &RepertoireArgs, mut output: impl Write) -> Result<(), Diagnostics> {
+    let mut diagnostics: Diagnostics = Diagnostics::new();
+
+    let mut tokenizer: Tokenizer = Tokenizer::new("", &args.repertoire_path);
+    tokenizer.set_control_width(args.machine.control_width);
+    tokenizer.set_widths(args.machine.address_bits, args.machine.data_bits);
+
+    let repertoire: Repertoire = match tokenizer.tokenize_repertoire(&mut diagnostics) {
+        Ok(repertoire) if diagnostics.is_empty() => repertoire,
+        Ok(_) => return Err(diagnostics),
+        Err(err) => {
+            diagnostics.push(err);
+            return Err(diagnostics);
+        }
+    };
 
-    let repertoire: HashMap<String, Instruction> = tokenizer.tokenize_repertoire()?;
+    let words = |words: &Vec<ControlWord>| words.iter().map(|word: &ControlWord| format!("{:X}", word.value())).collect::<Vec<String>>().join(" ");
+    let width: usize = repertoire.instructions().iter().map(|instruction: &Instruction| instruction.mnemonic().len()).max().unwrap_or_default().max(8);
+
+    let signatures: Vec<String> = repertoire.instructions().iter()
+        .map(|instruction: &Instruction| match instruction.operands().is_empty() {
+            true => "-".to_string(),
+            false => instruction.operands().iter().map(|operand: &Operand| operand.signature()).collect::<Vec<String>>().join(",")
+        })
+        .collect();
+    let operands_width: usize = signatures.iter().map(|signature: &String| signature.len()).max().unwrap_or_default().max(8);
+
+    writeln!(output, "Fetch: {}", words(repertoire.microprogram().fetch()))?;
+    writeln!(output, "OPCODE  {:<width$}  {:<operands_width$}  MICROCODE", "MNEMONIC", "OPERANDS")?;
+
+    for ((opcode, instruction), signature) in repertoire.instructions().iter().enumerate().zip(&signatures) {
+        writeln!(output, "{:<6}  {:<width$}  {:<operands_width$}  {}", opcode, instruction.mnemonic(), signature, words(instruction.microcode()))?;
+    }
+
+    Ok(())
+}
+
+/// Validates a program and runs it in the debugger, reading the commands from `input`.
+///
+/// ## Arguments
+///
+/// - `args` - A reference to the arguments of the `debug` subcommand.
+/// - `input` - Where the commands are read from.
+/// - `output` - Where the results of the commands are written.
+///
+/// ## Returns
+///
+/// Returns a `Result` indicating success (`Ok(())`) or every error found before debugging (`Err(Diagnostics)`).
+///
+pub fn debug(args: &DebugArgs, input: impl BufRead, output: impl Write) -> Result<(), Diagnostics> {
+    let (repertoire, tokens): (Repertoire, Program) = load(&args.source)?;
 
-    let tokens: Program = tokenizer.tokenize()?;
+    let mut debugger: Debugger = Debugger::new(&tokens, &repertoire)?;
 
-    let validator: Validator = Validator::new(tokens, &cli.output_path);
-    
-    validator.validate(&repertoire)?;
+    debugger.repl(input, output)?;
 
     Ok(())
+}
+
+/// Rebuilds the source of a program from its memory image and writes it to the output path.
+///
+/// ## Arguments
+///
+/// - `args` - A reference to the arguments of the `disasm` subcommand.
+///
+/// ## Returns
+///
+/// Returns a `Result` indicating success (`Ok(())`) or every error found (`Err(Diagnostics)`).
+///
+pub fn disasm(args: &DisasmArgs) -> Result<(), Diagnostics> {
+    let mut diagnostics: Diagnostics = Diagnostics::new();
+
+    let mut tokenizer: Tokenizer = Tokenizer::new("", &args.repertoire_path);
+    tokenizer.set_control_width(args.machine.control_width);
+    tokenizer.set_widths(args.machine.address_bits, args.machine.data_bits);
+
+    let repertoire: Result<Repertoire, SicompilerError> = tokenizer.tokenize_repertoire(&mut diagnostics);
+
+    let result: Result<(), SicompilerError> = repertoire.and_then(|repertoire: Repertoire| {
+        let init: u64 = Assembler::parse_address(&args.init, repertoire.machine(), &Span::default())?;
+        let content: Vec<u8> = files::read(&args.input_path)?;
+        let image: MemoryImage = Disassembler::read_image(files::display_name(&args.input_path), &content, args.format, repertoire.machine())?;
+
+        if !diagnostics.is_empty() {
+            return Ok(());
+        }
+
+        files::check_overwrite(&args.output_path, &[&args.input_path, &args.repertoire_path])?;
+        files::write(&args.output_path, Disassembler::disassemble(&image, init, &repertoire).as_bytes())
+    });
+
+    if let Err(err) = result {
+        diagnostics.push(err);
+    }
+
+    diagnostics.into_result()
 }
\ No newline at end of file
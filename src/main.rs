@@ -1,17 +1,60 @@
 use clap::Parser;
-use std::{time::Instant, process};
+use std::{env, io::{self, IsTerminal}, time::Instant, process};
 
-use sicompiler::{models::args::Cli, errors::error::SicompilerError};
+use sicompiler::{
+    models::args::{Cli, Commands, MessageFormat},
+    errors::{diagnostics::Diagnostics, renderer::Renderer, explain::explain, json},
+    structs::files::STDIO_PATH
+};
 
 fn main() {
     let cli: Cli = Cli::parse();
 
+    if let Some(code) = &cli.explain {
+        match explain(code) {
+            Some(explanation) => println!("{explanation}"),
+            None => {
+                eprintln!("error: '{code}' is not a valid error code");
+                process::exit(1);
+            }
+        }
+
+        return;
+    }
+
+    let Some(command) = &cli.command else { return };
     let now: Instant = Instant::now();
 
-    sicompiler::run(&cli).unwrap_or_else(|err: SicompilerError| {
-        eprintln!("{err}");
+    let result: Result<(), Diagnostics> = match command {
+        Commands::Check(args) => sicompiler::check(args),
+        Commands::Build(args) => sicompiler::build(args),
+        Commands::Run(args) => sicompiler::run(args, io::stdout()),
+        Commands::Fmt(args) => sicompiler::fmt(args),
+        Commands::Repertoire(args) => sicompiler::repertoire(args, io::stdout()),
+        Commands::Debug(args) => sicompiler::debug(args, io::stdin().lock(), io::stdout()),
+        Commands::Disasm(args) => sicompiler::disasm(args)
+    };
+
+    result.unwrap_or_else(|diagnostics: Diagnostics| {
+        match cli.message_format {
+            MessageFormat::Human => {
+                let color: bool = io::stderr().is_terminal() && env::var_os("NO_COLOR").is_none();
+                eprint!("{}", Renderer::new(color).render_all(&diagnostics));
+            },
+            MessageFormat::Json => print!("{}", json::render_all(&diagnostics))
+        }
+
         process::exit(1);
     });
-    
-    println!("Finished in {}s", now.elapsed().as_secs_f32());
+
+    //* The output written to the standard output is not mixed with the summary
+    let summary: bool = match command {
+        Commands::Check(_) => true,
+        Commands::Build(args) => args.output_path != STDIO_PATH && args.listing_path.as_deref() != Some(STDIO_PATH),
+        _ => false
+    };
+
+    if cli.message_format == MessageFormat::Human && summary {
+        println!("Finished in {}s", now.elapsed().as_secs_f32());
+    }
 }
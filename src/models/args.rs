@@ -1,16 +1,204 @@
-use clap::Parser;
+use clap::{Args, Parser, Subcommand, ValueEnum};
 
-#[derive(Parser)]
-#[command(name = "Sicompiler", author, version, about, long_about = None)]
-pub struct Cli {
-    /// The input path to compile
+use crate::structs::{tokenizer::DEFAULT_CONTROL_WIDTH, assembler::{MAX_ADDRESS_BITS, MAX_DATA_BITS}, simulator::DEFAULT_MAX_STEPS};
+
+/// The format used to print the diagnostics.
+#[derive(Debug, Clone, Copy, PartialEq, Default, ValueEnum)]
+pub enum MessageFormat {
+    /// Annotated messages meant to be read by people
+    #[default]
+    Human,
+    /// One JSON object per diagnostic and line, meant to be read by tools
+    Json,
+}
+
+/// The format of the output file.
+#[derive(Debug, Clone, Copy, PartialEq, Default, ValueEnum)]
+pub enum OutputFormat {
+    /// The program with its labels and variable names resolved to hex addresses
+    #[default]
+    Text,
+    /// The assembled memory image, each word in big-endian order from address 0
+    Bin,
+    /// Logisim-evolution `v2.0 raw` images of the memory and of the control ROM
+    Logisim,
+    /// Hex images of the memory and of the control ROM for the Digital simulator
+    Digital,
+    /// The used memory cells as an Intel HEX file, at their byte address
+    Ihex,
+    /// The used memory cells as a Motorola S-record file, at their byte address
+    Srec,
+}
+
+/// The format of a memory image read by the disassembler.
+#[derive(Debug, Clone, Copy, PartialEq, Default, ValueEnum)]
+pub enum ImageFormat {
+    /// Each word in big-endian order from address 0, as written by `--format bin`
+    #[default]
+    Bin,
+    /// A Logisim-evolution `v2.0 raw` file
+    Logisim,
+    /// A hex file for the Digital simulator, one word per line
+    Digital,
+}
+
+/// The widths of the machine a repertoire runs on, shared by the subcommands that read a repertoire.
+#[derive(Args)]
+pub struct MachineArgs {
+    /// The number of control signals each control word of the repertoire can use
+    #[arg(long = "control-width", value_name = "BITS", default_value_t = DEFAULT_CONTROL_WIDTH, value_parser = clap::value_parser!(u32).range(1..=64))]
+    pub control_width: u32,
+
+    /// The address bits of the machine, overriding the ones of the repertoire header
+    #[arg(long = "address-bits", value_name = "BITS", value_parser = clap::value_parser!(u32).range(1..=MAX_ADDRESS_BITS as i64))]
+    pub address_bits: Option<u32>,
+
+    /// The data bits of the machine, overriding the ones of the repertoire header
+    #[arg(long = "data-bits", value_name = "BITS", value_parser = clap::value_parser!(u32).range(1..=MAX_DATA_BITS as i64))]
+    pub data_bits: Option<u32>,
+}
+
+impl Default for MachineArgs {
+    fn default() -> Self {
+        MachineArgs { control_width: DEFAULT_CONTROL_WIDTH, address_bits: None, data_bits: None }
+    }
+}
+
+/// The program and the repertoire it is checked against, shared by the subcommands that read a program.
+#[derive(Args)]
+pub struct SourceArgs {
+    /// The input path of the program, `-` to read it from the standard input
     pub input_path: String,
 
+    /// The repertoire of instructions
+    #[arg(short = 'r', long = "rep")]
+    pub repertoire_path: String,
+
+    #[command(flatten)]
+    pub machine: MachineArgs,
+}
+
+/// The arguments of the `check` subcommand.
+#[derive(Args)]
+pub struct CheckArgs {
+    #[command(flatten)]
+    pub source: SourceArgs,
+}
+
+/// The arguments of the `build` subcommand.
+#[derive(Args)]
+pub struct BuildArgs {
+    #[command(flatten)]
+    pub source: SourceArgs,
+
+    /// The output path to write to, `-` to write to the standard output
     #[arg(short = 'o', long = "out", default_value = "out.txt")]
-    /// The output path to write to
     pub output_path: String,
 
-    /// The repertoire of instructions
+    /// The format of the output file
+    #[arg(long = "format", value_enum, default_value_t = OutputFormat::Text)]
+    pub format: OutputFormat,
+
+    /// The path to write a listing with the address and the encoding of every line to, `-` for the standard output
+    #[arg(long = "listing", value_name = "PATH")]
+    pub listing_path: Option<String>,
+}
+
+/// The arguments of the `run` subcommand.
+#[derive(Args)]
+pub struct RunArgs {
+    #[command(flatten)]
+    pub source: SourceArgs,
+
+    /// The number of microsteps the program can run before it is stopped
+    #[arg(long = "max-steps", value_name = "STEPS", default_value_t = DEFAULT_MAX_STEPS)]
+    pub max_steps: usize,
+}
+
+/// The arguments of the `fmt` subcommand.
+#[derive(Args)]
+pub struct FmtArgs {
+    /// The input path of the program to format, `-` to read it from the standard input and write it to the standard output
+    pub input_path: String,
+
+    /// Check that the program is formatted instead of rewriting it
+    #[arg(long = "check")]
+    pub check: bool,
+}
+
+/// The arguments of the `repertoire` subcommand.
+#[derive(Args)]
+pub struct RepertoireArgs {
+    /// The repertoire of instructions to inspect
+    pub repertoire_path: String,
+
+    #[command(flatten)]
+    pub machine: MachineArgs,
+}
+
+/// The arguments of the `debug` subcommand.
+#[derive(Args)]
+pub struct DebugArgs {
+    #[command(flatten)]
+    pub source: SourceArgs,
+}
+
+/// The arguments of the `disasm` subcommand.
+#[derive(Args)]
+pub struct DisasmArgs {
+    /// The memory image to disassemble, `-` to read it from the standard input
+    pub input_path: String,
+
+    /// The output path to write the program to, `-` to write to the standard output
+    #[arg(short = 'o', long = "out", default_value = "out.txt")]
+    pub output_path: String,
+
+    /// The repertoire the image was assembled with
     #[arg(short = 'r', long = "rep")]
     pub repertoire_path: String,
+
+    /// The format of the memory image
+    #[arg(long = "format", value_enum, default_value_t = ImageFormat::Bin)]
+    pub format: ImageFormat,
+
+    /// The address of the first instruction, in hex base
+    #[arg(long = "init", value_name = "ADDR", default_value = "0")]
+    pub init: String,
+
+    #[command(flatten)]
+    pub machine: MachineArgs,
+}
+
+#[derive(Subcommand)]
+pub enum Commands {
+    /// Validate a program without writing anything
+    Check(CheckArgs),
+    /// Validate a program and write it in the chosen format
+    Build(BuildArgs),
+    /// Run a program in the simulator and print the registers and the variables when it halts
+    Run(RunArgs),
+    /// Format the source of a program
+    Fmt(FmtArgs),
+    /// Print the opcodes and the microcode of a repertoire
+    Repertoire(RepertoireArgs),
+    /// Run a program step by step, with breakpoints, watchpoints and reverse steps
+    Debug(DebugArgs),
+    /// Rebuild the source of a program from its memory image
+    Disasm(DisasmArgs),
+}
+
+#[derive(Parser)]
+#[command(name = "Sicompiler", author, version, about, long_about = None)]
+#[command(arg_required_else_help = true)]
+pub struct Cli {
+    #[command(subcommand)]
+    pub command: Option<Commands>,
+
+    /// The format used to print errors
+    #[arg(long = "message-format", value_name = "FORMAT", value_enum, default_value_t = MessageFormat::Human, global = true)]
+    pub message_format: MessageFormat,
+
+    /// Print a long description of an error code, e.g. SC0013
+    #[arg(long = "explain", value_name = "CODE", exclusive = true)]
+    pub explain: Option<String>,
 }
\ No newline at end of file
@@ -0,0 +1,39 @@
+use super::span::Span;
+
+/// Represents an assembly-time constant, a name for a value that does not occupy memory.
+#[derive(Debug, PartialEq, Clone)]
+pub struct Constant {
+    name: String,
+    value: String,
+    index: usize,
+    span: Span,
+    value_span: Span,
+}
+
+impl Constant {
+    /// Creates a new `Constant` with the specified name and value.
+    ///
+    /// ## Arguments
+    ///
+    /// - `name` - The name of the constant.
+    /// - `value` - The value of the constant, a number or an expression.
+    /// - `index` - The number of instructions written before the constant, which gives the value of `$`.
+    ///
+    /// ## Returns
+    ///
+    /// A new `Constant` instance.
+    pub fn new(name: &str, value: &str, index: usize) -> Constant {
+        Constant { name: name.to_string(), value: value.to_string(), index, span: Span::default(), value_span: Span::default() }
+    }
+
+    pub fn name(&self) -> &str { &self.name }
+    pub fn value(&self) -> &str { &self.value }
+    pub fn index(&self) -> usize { self.index }
+    pub fn span(&self) -> &Span { &self.span }
+    pub fn value_span(&self) -> &Span { &self.value_span }
+
+    pub fn set_spans(&mut self, span: Span, value_span: Span) {
+        self.span = span;
+        self.value_span = value_span;
+    }
+}
\ No newline at end of file
@@ -0,0 +1,278 @@
+/// Represents the content of the memory of the machine: the encoded instructions and the variables
+/// at their addresses, and which cells the program uses.
+#[derive(Debug, PartialEq, Clone)]
+pub struct MemoryImage {
+    words: Vec<u64>,
+    used: Vec<bool>,
+    word_bits: u32,
+}
+
+impl MemoryImage {
+    /// Creates a new empty `MemoryImage`.
+    ///
+    /// ## Arguments
+    ///
+    /// - `size` - The number of cells of the memory.
+    /// - `word_bits` - The number of bits of each cell.
+    ///
+    /// ## Returns
+    ///
+    /// A new `MemoryImage` instance with every cell set to `0` and unused.
+    pub fn new(size: usize, word_bits: u32) -> MemoryImage {
+        MemoryImage { words: vec![0; size], used: vec![false; size], word_bits }
+    }
+
+    pub fn words(&self) -> &Vec<u64> { &self.words }
+    pub fn word_bits(&self) -> u32 { self.word_bits }
+
+    /// Returns the word stored at an address, `0` if it is outside the memory.
+    pub fn get(&self, address: u64) -> u64 { self.words.get(address as usize).copied().unwrap_or_default() }
+
+    /// Checks whether the program stores anything at an address.
+    pub fn is_used(&self, address: u64) -> bool { self.used.get(address as usize).copied().unwrap_or_default() }
+
+    /// Stores a word at an address and marks it as used.
+    pub fn set(&mut self, address: u64, word: u64) {
+        self.words[address as usize] = word;
+        self.used[address as usize] = true;
+    }
+
+    /// Returns the number of cells from address `0` up to the last used one.
+    pub fn used_len(&self) -> usize {
+        self.used.iter().rposition(|used: &bool| *used).map_or(0, |last: usize| last + 1)
+    }
+
+    /// Returns the number of bytes each word takes in the binary image.
+    pub fn word_bytes(&self) -> usize { self.word_bits.div_ceil(8) as usize }
+
+    /// Encodes words as raw bytes, each word in big-endian order and padded to whole bytes.
+    fn bytes(&self, words: &[u64]) -> Vec<u8> {
+        let word_bytes: usize = self.word_bytes();
+
+        words.iter()
+            .flat_map(|word: &u64| word.to_be_bytes()[8 - word_bytes..].to_vec())
+            .collect()
+    }
+
+    /// Returns every run of consecutive used cells, with the address where it starts.
+    pub fn segments(&self) -> Vec<(u64, &[u64])> {
+        let mut segments: Vec<(u64, &[u64])> = Vec::new();
+        let mut start: Option<usize> = None;
+
+        for address in 0..=self.used.len() {
+            let used: bool = self.used.get(address).copied().unwrap_or_default();
+
+            match (start, used) {
+                (None, true) => start = Some(address),
+                (Some(first), false) => {
+                    segments.push((first as u64, &self.words[first..address]));
+                    start = None;
+                },
+                _ => {}
+            }
+        }
+
+        segments
+    }
+
+    /// Encodes the memory from address `0` up to the last used cell as raw bytes, each word in
+    /// big-endian order and padded to whole bytes.
+    pub fn to_bin(&self) -> Vec<u8> { self.bytes(&self.words[..self.used_len()]) }
+
+    /// Splits the used cells in records of up to 16 bytes, each of them with its byte address.
+    fn records(&self) -> Vec<(u64, Vec<u8>)> {
+        let word_bytes: u64 = self.word_bytes() as u64;
+
+        self.segments().iter()
+            .flat_map(|(start, words): &(u64, &[u64])| {
+                self.bytes(words).chunks(16)
+                    .enumerate()
+                    .map(|(index, data): (usize, &[u8])| (start * word_bytes + index as u64 * 16, data.to_vec()))
+                    .collect::<Vec<(u64, Vec<u8>)>>()
+            })
+            .collect()
+    }
+
+    /// Encodes the used cells as an Intel HEX file, at their byte address: the address of the cell
+    /// times the bytes of each word.
+    pub fn to_ihex(&self) -> String {
+        let record = |address: u64, kind: u8, data: &[u8]| {
+            let bytes: Vec<u8> = [vec![data.len() as u8, (address >> 8) as u8, address as u8, kind], data.to_vec()].concat();
+            let checksum: u8 = bytes.iter().fold(0u8, |sum: u8, byte: &u8| sum.wrapping_add(*byte)).wrapping_neg();
+
+            format!(":{}{:02X}\n", hex(&bytes), checksum)
+        };
+
+        let data: String = self.records().iter()
+            .map(|(address, data): &(u64, Vec<u8>)| record(*address, 0x00, data))
+            .collect();
+
+        data + &record(0, 0x01, &[])
+    }
+
+    /// Encodes the used cells as a Motorola S-record file, at their byte address like `to_ihex`.
+    ///
+    /// ## Arguments
+    ///
+    /// - `start` - The address of the cell where the program starts, written in the termination record.
+    pub fn to_srec(&self, start: u64) -> String {
+        let record = |kind: u8, address: u64, data: &[u8]| {
+            let bytes: Vec<u8> = [vec![data.len() as u8 + 3, (address >> 8) as u8, address as u8], data.to_vec()].concat();
+            let checksum: u8 = !bytes.iter().fold(0u8, |sum: u8, byte: &u8| sum.wrapping_add(*byte));
+
+            format!("S{}{}{:02X}\n", kind, hex(&bytes), checksum)
+        };
+
+        let records: Vec<(u64, Vec<u8>)> = self.records();
+        let data: String = records.iter()
+            .map(|(address, data): &(u64, Vec<u8>)| record(1, *address, data))
+            .collect();
+
+        record(0, 0, &[]) + &data + &record(5, records.len() as u64, &[]) + &record(9, start * self.word_bytes() as u64, &[])
+    }
+
+    /// Encodes the memory from address `0` up to the last used cell as a Logisim-evolution `v2.0 raw`
+    /// file, eight words per line, writing runs of four or more equal words as `N*word`.
+    pub fn to_logisim(&self) -> String {
+        let mut runs: Vec<String> = Vec::new();
+        let mut words = self.words[..self.used_len()].iter().peekable();
+
+        while let Some(word) = words.next() {
+            let mut count: usize = 1;
+
+            while words.next_if_eq(&word).is_some() {
+                count += 1;
+            }
+
+            if count >= 4 {
+                runs.push(format!("{}*{:x}", count, word));
+            } else {
+                runs.extend(std::iter::repeat_n(format!("{:x}", word), count));
+            }
+        }
+
+        let lines: Vec<String> = runs.chunks(8).map(|line: &[String]| line.join(" ") + "\n").collect();
+
+        format!("v2.0 raw\n{}", lines.concat())
+    }
+
+    /// Encodes the memory from address `0` up to the last used cell as a hex file for the Digital
+    /// simulator, one word per line.
+    pub fn to_digital(&self) -> String {
+        self.words[..self.used_len()].iter()
+            .map(|word: &u64| format!("{:x}\n", word))
+            .collect()
+    }
+}
+
+/// Writes bytes as uppercase hex digits.
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte: &u8| format!("{:02X}", byte)).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_to_bin() {
+        let mut image: MemoryImage = MemoryImage::new(16, 17);
+
+        assert_eq!(image.used_len(), 0);
+        assert!(image.to_bin().is_empty());
+
+        image.set(0, 0x3);
+        image.set(2, 0x1F001);
+
+        assert_eq!(image.used_len(), 3);
+        assert!(!image.is_used(1));
+        assert_eq!(image.get(2), 0x1F001);
+        assert_eq!(image.to_bin(), vec![0, 0, 3, 0, 0, 0, 1, 0xF0, 0x01]);
+    }
+
+    #[test]
+    fn test_segments() {
+        let mut image: MemoryImage = MemoryImage::new(16, 17);
+
+        assert!(image.segments().is_empty());
+
+        image.set(0, 0x3);
+        image.set(1, 0x4);
+        image.set(6, 0x1001);
+        image.set(15, 0x0);
+
+        assert_eq!(image.segments(), vec![(0, &[0x3, 0x4][..]), (6, &[0x1001][..]), (15, &[0x0][..])]);
+    }
+
+    #[test]
+    fn test_to_ihex() {
+        let mut image: MemoryImage = MemoryImage::new(4096, 17);
+
+        assert_eq!(image.to_ihex(), ":00000001FF\n");
+
+        image.set(0, 0x3);
+        image.set(1, 0x3);
+        image.set(6, 0x1001);
+        image.set(7, 0x0);
+
+        assert_eq!(image.to_ihex(), ":06000000000003000003F4\n:06001200001001000000D7\n:00000001FF\n");
+
+        for address in 0x100..0x106 {
+            image.set(address, 0x1FFFF);
+        }
+
+        assert!(image.to_ihex().contains(":10030000\
+01FFFF01FFFF01FFFF01FFFF01FFFF01\
+F1\n:02031000FFFFED\n"));
+    }
+
+    #[test]
+    fn test_to_srec() {
+        let mut image: MemoryImage = MemoryImage::new(4096, 17);
+
+        assert_eq!(image.to_srec(0), "S0030000FC\nS5030000FC\nS9030000FC\n");
+
+        image.set(0, 0x3);
+        image.set(1, 0x3);
+        image.set(6, 0x1001);
+        image.set(7, 0x0);
+
+        assert_eq!(image.to_srec(6), "S0030000FC\nS1090000000003000003F0\nS1090012001001000000D3\nS5030002FA\nS9030012EA\n");
+    }
+
+    #[test]
+    fn test_to_logisim() {
+        let mut image: MemoryImage = MemoryImage::new(32, 17);
+
+        assert_eq!(image.to_logisim(), "v2.0 raw\n");
+
+        image.set(0, 0x3);
+        image.set(1, 0x3);
+        image.set(7, 0x1A);
+        image.set(10, 0x1F001);
+
+        for address in 11..18 {
+            image.set(address, 0x1);
+        }
+
+        assert_eq!(image.to_logisim(), "v2.0 raw\n3 3 5*0 1a 0 0 1f001 7*1\n");
+
+        image.set(20, 0x1);
+        image.set(25, 0x2);
+        image.set(26, 0x2);
+
+        assert_eq!(image.to_logisim(), "v2.0 raw\n3 3 5*0 1a 0 0 1f001 7*1\n0 0 1 4*0 2 2\n");
+    }
+
+    #[test]
+    fn test_to_digital() {
+        let mut image: MemoryImage = MemoryImage::new(16, 17);
+
+        assert_eq!(image.to_digital(), "");
+
+        image.set(0, 0x3);
+        image.set(2, 0x1F001);
+
+        assert_eq!(image.to_digital(), "3\n0\n1f001\n");
+    }
+}
\ No newline at end of file
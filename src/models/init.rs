@@ -1,15 +1,26 @@
+use super::span::Span;
+
 /// Represents the initialization section of a program.
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct Init {
     dir: String,
+    span: Span,
 }
 
 impl Init {
     pub fn new(dir: &str) -> Init {
-        Init { dir: dir.to_string() }
+        Init { dir: dir.to_string(), span: Span::default() }
     }
 
     pub fn dir(&self) -> &str {
         &self.dir
     }
+
+    pub fn span(&self) -> &Span {
+        &self.span
+    }
+
+    pub fn set_span(&mut self, span: Span) {
+        self.span = span;
+    }
 }
\ No newline at end of file
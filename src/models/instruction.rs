@@ -1,9 +1,15 @@
+use super::{span::Span, microprogram::ControlWord, operand::Operand};
+
 /// Represents an instruction in a program.
 #[derive(Debug, PartialEq, Clone)]
 pub struct Instruction {
     mnemonic: String,
     flag: bool,
     params: Vec<String>,
+    span: Span,
+    param_spans: Vec<Span>,
+    microcode: Vec<ControlWord>,
+    operands: Vec<Operand>,
 }
 
 impl Instruction {
@@ -18,17 +24,41 @@ impl Instruction {
     ///
     /// A new `Instruction` instance.
     pub fn new(mnemonic: &str, params: Vec<&str>) -> Instruction {
+        let param_spans: Vec<Span> = vec![Span::default(); params.len()];
+
         if params.is_empty() {
-            return Instruction { mnemonic: mnemonic.to_string(), flag: false, params: vec![] };
+            return Instruction { mnemonic: mnemonic.to_string(), flag: false, params: vec![], span: Span::default(), param_spans, microcode: vec![], operands: vec![] };
         }
         
-        Instruction { mnemonic: mnemonic.to_string(), flag: true, params: params.iter().map(|s| String::from(*s)).collect() }
+        Instruction { mnemonic: mnemonic.to_string(), flag: true, params: params.iter().map(|s| String::from(*s)).collect(), span: Span::default(), param_spans, microcode: vec![], operands: vec![] }
     }
 
     pub fn mnemonic(&self) -> &str { &self.mnemonic }
     pub fn flag(&self) -> bool { self.flag }
     pub fn params(&self) -> &Vec<String> { &self.params }
+    pub fn span(&self) -> &Span { &self.span }
+    pub fn param_spans(&self) -> &Vec<Span> { &self.param_spans }
+
+    /// The microsteps run to execute the instruction, only declared in repertoire instructions.
+    pub fn microcode(&self) -> &Vec<ControlWord> { &self.microcode }
+
+    /// The signature of the operands, only declared in repertoire instructions.
+    pub fn operands(&self) -> &Vec<Operand> { &self.operands }
+
+    /// Returns the span of the parameter at `index`, or the span of the mnemonic if it has no location.
+    pub fn param_span(&self, index: usize) -> &Span {
+        self.param_spans.get(index).filter(|span: &&Span| !span.is_unknown()).unwrap_or(&self.span)
+    }
 
     pub fn set_flag(&mut self, flag: bool) { self.flag = flag; }
     pub fn set_params(&mut self, params: Vec<&str>) { self.params = params.iter().map(|s| String::from(*s)).collect(); }
+    pub fn set_span(&mut self, span: Span) { self.span = span; }
+    pub fn set_param_spans(&mut self, param_spans: Vec<Span>) { self.param_spans = param_spans; }
+    pub fn set_microcode(&mut self, microcode: Vec<ControlWord>) { self.microcode = microcode; }
+
+    /// Sets the signature of the operands of a repertoire instruction, which takes operands if it is not empty.
+    pub fn set_operands(&mut self, operands: Vec<Operand>) {
+        self.flag = !operands.is_empty();
+        self.operands = operands;
+    }
 }
\ No newline at end of file
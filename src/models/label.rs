@@ -0,0 +1,31 @@
+use super::span::Span;
+
+/// Represents a label of the instructions section, naming the address of the instruction that follows it.
+#[derive(Debug, PartialEq, Clone)]
+pub struct Label {
+    name: String,
+    index: usize,
+    span: Span,
+}
+
+impl Label {
+    /// Creates a new `Label` with the specified name and instruction.
+    ///
+    /// ## Arguments
+    ///
+    /// - `name` - The name of the label, without the `:`.
+    /// - `index` - The position of the labelled instruction in the instructions section.
+    ///
+    /// ## Returns
+    ///
+    /// A new `Label` instance.
+    pub fn new(name: &str, index: usize) -> Label {
+        Label { name: name.to_string(), index, span: Span::default() }
+    }
+
+    pub fn name(&self) -> &str { &self.name }
+    pub fn index(&self) -> usize { self.index }
+    pub fn span(&self) -> &Span { &self.span }
+
+    pub fn set_span(&mut self, span: Span) { self.span = span; }
+}
\ No newline at end of file
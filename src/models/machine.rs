@@ -0,0 +1,83 @@
+use crate::structs::assembler::{OPCODE_BITS, ADDRESS_BITS, DATA_BITS};
+
+/// Represents the widths of the machine a repertoire runs on: the bits of its addresses, which give
+/// the size of the memory and of the operand field of the instructions, and the bits of its data words.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct Machine {
+    address_bits: u32,
+    data_bits: u32,
+}
+
+impl Machine {
+    /// Creates a new `Machine` with the specified widths.
+    ///
+    /// ## Arguments
+    ///
+    /// - `address_bits` - The number of bits of a memory address.
+    /// - `data_bits` - The number of bits of a data word.
+    ///
+    /// ## Returns
+    ///
+    /// A new `Machine` instance.
+    pub fn new(address_bits: u32, data_bits: u32) -> Machine {
+        Machine { address_bits, data_bits }
+    }
+
+    pub fn address_bits(&self) -> u32 { self.address_bits }
+    pub fn data_bits(&self) -> u32 { self.data_bits }
+
+    /// The number of cells of the memory.
+    pub fn memory_size(&self) -> usize { 1 << self.address_bits }
+
+    /// The largest address of the memory.
+    pub fn address_max(&self) -> u64 { (1 << self.address_bits) - 1 }
+
+    /// The largest value of a data word.
+    pub fn data_max(&self) -> u64 { (1 << self.data_bits) - 1 }
+
+    /// The number of bits of a memory cell, wide enough for an encoded instruction and for a data word.
+    pub fn word_bits(&self) -> u32 { (OPCODE_BITS + self.address_bits).max(self.data_bits) }
+
+    /// Encodes an instruction as a machine word, its opcode followed by its operand field.
+    pub fn encode(&self, opcode: usize, operand: u64) -> u64 { (opcode as u64) << self.address_bits | operand & self.address_max() }
+
+    /// Returns the opcode of an encoded instruction.
+    pub fn opcode(&self, word: u64) -> u64 { word >> self.address_bits }
+
+    /// Returns the operand field of an encoded instruction.
+    pub fn operand(&self, word: u64) -> u64 { word & self.address_max() }
+
+    pub fn set_address_bits(&mut self, address_bits: u32) { self.address_bits = address_bits; }
+    pub fn set_data_bits(&mut self, data_bits: u32) { self.data_bits = data_bits; }
+}
+
+impl Default for Machine {
+    /// The SiCoMe machine: 12-bit addresses and 16-bit data words.
+    fn default() -> Machine {
+        Machine::new(ADDRESS_BITS, DATA_BITS)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_machine() {
+        let machine: Machine = Machine::default();
+
+        assert_eq!(machine.memory_size(), 4096);
+        assert_eq!(machine.address_max(), 0xFFF);
+        assert_eq!(machine.data_max(), 0xFFFF);
+        assert_eq!(machine.word_bits(), 17);
+        assert_eq!(machine.encode(31, 0xFFF), (1 << 17) - 1);
+        assert_eq!(machine.encode(1, 0x1FFF), 0x1FFF);
+        assert_eq!(machine.opcode(0x1ABC), 1);
+        assert_eq!(machine.operand(0x1ABC), 0xABC);
+
+        let machine: Machine = Machine::new(8, 24);
+
+        assert_eq!(machine.word_bits(), 24);
+        assert_eq!(machine.encode(2, 0x12), 0x212);
+    }
+}
\ No newline at end of file
@@ -0,0 +1,51 @@
+use super::span::Span;
+
+/// Represents a control word of the microprogram: the signals driven in one microstep.
+#[derive(Debug, PartialEq, Clone)]
+pub struct ControlWord {
+    value: u64,
+    span: Span,
+}
+
+impl ControlWord {
+    /// Creates a new `ControlWord` with the specified value and location.
+    ///
+    /// ## Arguments
+    ///
+    /// - `value` - The control signals, one per bit.
+    /// - `span` - The location of the word in the repertoire.
+    ///
+    /// ## Returns
+    ///
+    /// A new `ControlWord` instance.
+    pub fn new(value: u64, span: Span) -> ControlWord {
+        ControlWord { value, span }
+    }
+
+    pub fn value(&self) -> u64 { self.value }
+    pub fn span(&self) -> &Span { &self.span }
+}
+
+/// Represents the microprogram section of a repertoire, with the control words of the fetch cycle
+/// that run before every instruction.
+#[derive(Debug, PartialEq, Clone, Default)]
+pub struct Microprogram {
+    fetch: Vec<ControlWord>,
+}
+
+impl Microprogram {
+    /// Creates a new `Microprogram` with the specified fetch cycle.
+    ///
+    /// ## Arguments
+    ///
+    /// - `fetch` - The control words of the fetch cycle, in order.
+    ///
+    /// ## Returns
+    ///
+    /// A new `Microprogram` instance.
+    pub fn new(fetch: Vec<ControlWord>) -> Microprogram {
+        Microprogram { fetch }
+    }
+
+    pub fn fetch(&self) -> &Vec<ControlWord> { &self.fetch }
+}
\ No newline at end of file
@@ -1,5 +1,15 @@
 pub mod instruction;
+pub mod operand;
 pub mod variable;
 pub mod init;
 pub mod program;
-pub mod args;
\ No newline at end of file
+pub mod args;
+pub mod span;
+pub mod source;
+pub mod microprogram;
+pub mod repertoire;
+pub mod label;
+pub mod constant;
+pub mod symbol;
+pub mod image;
+pub mod machine;
\ No newline at end of file
@@ -0,0 +1,79 @@
+/// The kind of value an operand of an instruction holds.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum OperandKind {
+    /// A memory address, written in hex base or as a label or a variable name.
+    Address,
+    /// A constant, written in hex base or as a label or a variable name.
+    Immediate,
+    /// A register, written as `R` followed by its number in decimal, e.g. `R3`.
+    Register,
+}
+
+impl OperandKind {
+    /// Returns the kind with the specified name in a repertoire signature, written in full or abbreviated.
+    pub fn from_name(name: &str) -> Option<OperandKind> {
+        match name {
+            "addr" | "address" => Some(OperandKind::Address),
+            "imm" | "immediate" => Some(OperandKind::Immediate),
+            "reg" | "register" => Some(OperandKind::Register),
+            _ => None
+        }
+    }
+
+    pub fn name(&self) -> &str {
+        match self {
+            OperandKind::Address => "address",
+            OperandKind::Immediate => "immediate",
+            OperandKind::Register => "register"
+        }
+    }
+}
+
+/// Represents an operand a repertoire instruction takes: its kind and the bits it fills in the encoded word.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct Operand {
+    kind: OperandKind,
+    width: u32,
+}
+
+impl Operand {
+    /// Creates a new `Operand` with the specified kind and width.
+    ///
+    /// ## Arguments
+    ///
+    /// - `kind` - The kind of value the operand holds.
+    /// - `width` - The number of bits of the operand.
+    ///
+    /// ## Returns
+    ///
+    /// A new `Operand` instance.
+    pub fn new(kind: OperandKind, width: u32) -> Operand {
+        Operand { kind, width }
+    }
+
+    pub fn kind(&self) -> OperandKind { self.kind }
+    pub fn width(&self) -> u32 { self.width }
+
+    /// Returns the largest value the operand can hold.
+    pub fn max(&self) -> u64 { (1u64 << self.width) - 1 }
+
+    /// Returns the signature of the operand as written in a repertoire, e.g. `address:12`.
+    pub fn signature(&self) -> String { format!("{}:{}", self.kind.name(), self.width) }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_operand() {
+        assert_eq!(OperandKind::from_name("addr"), Some(OperandKind::Address));
+        assert_eq!(OperandKind::from_name("register"), Some(OperandKind::Register));
+        assert_eq!(OperandKind::from_name("true"), None);
+
+        let operand: Operand = Operand::new(OperandKind::Immediate, 8);
+
+        assert_eq!(operand.max(), 0xFF);
+        assert_eq!(operand.signature(), "immediate:8");
+    }
+}
\ No newline at end of file
@@ -1,8 +1,8 @@
-use super::{variable::Variable, init::Init, instruction::Instruction};
+use super::{variable::Variable, init::Init, instruction::Instruction, span::Span, label::Label, constant::Constant};
 
 /// Represents a complete program, consisting of variables, initialization information, and instructions.
-#[derive(Debug)]
-pub struct Program(Vec<Variable>, Init, Vec<Instruction>);
+#[derive(Debug, Clone)]
+pub struct Program(Vec<Variable>, Init, Vec<Instruction>, Span, Vec<Label>, Vec<Constant>);
 
 impl Program {
     /// Creates a new `Program` with the specified variables, initialization information, and instructions.
@@ -17,10 +17,23 @@ impl Program {
     ///
     /// A new `Program` instance.
     pub fn new(variables: Vec<Variable>, init: Init, instructions: Vec<Instruction>) -> Program {
-        Program(variables, init, instructions)
+        Program(variables, init, instructions, Span::default(), Vec::new(), Vec::new())
     }
 
     pub fn variables(&self) -> &Vec<Variable> { &self.0 }
     pub fn init(&self) -> &Init { &self.1 }
     pub fn instructions(&self) -> &Vec<Instruction> { &self.2 }
+
+    /// The span of the whole source file the program was read from.
+    pub fn span(&self) -> &Span { &self.3 }
+
+    /// The labels of the instructions section, in the order they are written.
+    pub fn labels(&self) -> &Vec<Label> { &self.4 }
+
+    /// The constants of the program, in the order they are defined.
+    pub fn constants(&self) -> &Vec<Constant> { &self.5 }
+
+    pub fn set_span(&mut self, span: Span) { self.3 = span; }
+    pub fn set_labels(&mut self, labels: Vec<Label>) { self.4 = labels; }
+    pub fn set_constants(&mut self, constants: Vec<Constant>) { self.5 = constants; }
 }
\ No newline at end of file
@@ -0,0 +1,51 @@
+use super::{instruction::Instruction, microprogram::Microprogram, machine::Machine};
+
+/// Represents a repertoire: the microprogram and the valid instructions, in the order they are declared.
+#[derive(Debug, Clone, Default)]
+pub struct Repertoire {
+    microprogram: Microprogram,
+    instructions: Vec<Instruction>,
+    machine: Machine,
+    path: String,
+}
+
+impl Repertoire {
+    /// Creates a new `Repertoire` with the specified microprogram and instructions.
+    ///
+    /// ## Arguments
+    ///
+    /// - `microprogram` - The microprogram section.
+    /// - `instructions` - The valid instructions, in the order they are declared.
+    ///
+    /// ## Returns
+    ///
+    /// A new `Repertoire` instance.
+    pub fn new(microprogram: Microprogram, instructions: Vec<Instruction>) -> Repertoire {
+        Repertoire { microprogram, instructions, machine: Machine::default(), path: String::new() }
+    }
+
+    pub fn microprogram(&self) -> &Microprogram { &self.microprogram }
+    pub fn instructions(&self) -> &Vec<Instruction> { &self.instructions }
+    pub fn len(&self) -> usize { self.instructions.len() }
+    pub fn is_empty(&self) -> bool { self.instructions.is_empty() }
+
+    /// The widths of the machine the repertoire runs on, declared in its header.
+    pub fn machine(&self) -> &Machine { &self.machine }
+
+    pub fn set_machine(&mut self, machine: Machine) { self.machine = machine; }
+
+    /// The path of the file the repertoire was read from, empty if it was not read from a file.
+    pub fn path(&self) -> &str { &self.path }
+
+    pub fn set_path(&mut self, path: &str) { self.path = path.to_string(); }
+
+    /// Returns the instruction with the specified mnemonic, if it is declared.
+    pub fn get(&self, mnemonic: &str) -> Option<&Instruction> {
+        self.instructions.iter().find(|instruction: &&Instruction| instruction.mnemonic() == mnemonic)
+    }
+
+    /// Returns the opcode of the instruction with the specified mnemonic: its position in the repertoire.
+    pub fn opcode(&self, mnemonic: &str) -> Option<usize> {
+        self.instructions.iter().position(|instruction: &Instruction| instruction.mnemonic() == mnemonic)
+    }
+}
\ No newline at end of file
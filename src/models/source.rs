@@ -0,0 +1,71 @@
+use super::span::Span;
+
+/// Represents the content of a source file together with its path,
+/// used to translate byte offsets into spans.
+#[derive(Debug)]
+pub struct SourceFile {
+    path: String,
+    content: String,
+    line_starts: Vec<usize>,
+}
+
+impl SourceFile {
+    /// Creates a new `SourceFile` with the specified path and content.
+    ///
+    /// ## Arguments
+    ///
+    /// - `path` - The path the content was read from.
+    /// - `content` - The content of the file.
+    ///
+    /// ## Returns
+    ///
+    /// A new `SourceFile` instance.
+    pub fn new(path: &str, content: &str) -> SourceFile {
+        let line_starts: Vec<usize> = std::iter::once(0)
+            .chain(content.match_indices('\n').map(|(index, _)| index + 1))
+            .collect();
+
+        SourceFile { path: path.to_string(), content: content.to_string(), line_starts }
+    }
+
+    pub fn path(&self) -> &str { &self.path }
+    pub fn content(&self) -> &str { &self.content }
+
+    /// Builds the span of `len` bytes starting at `offset` in the content.
+    ///
+    /// ## Arguments
+    ///
+    /// - `offset` - The byte offset of the token in the content.
+    /// - `len` - The length of the token in bytes.
+    ///
+    /// ## Returns
+    ///
+    /// A `Span` with the line and column (counted in characters) of the token.
+    pub fn span_at(&self, offset: usize, len: usize) -> Span {
+        let offset: usize = offset.min(self.content.len());
+        let line_index: usize = self.line_starts.partition_point(|start: &usize| *start <= offset) - 1;
+        let line_start: usize = self.line_starts[line_index];
+        let end: usize = (offset + len).min(self.content.len());
+
+        let column: usize = self.content.get(line_start..offset).map_or(0, |s: &str| s.chars().count()) + 1;
+        let len: usize = self.content.get(offset..end).map_or(len, |s: &str| s.chars().count());
+
+        Span::new(&self.path, line_index + 1, column, len)
+    }
+
+    /// Returns the text of a line without its line break.
+    ///
+    /// ## Arguments
+    ///
+    /// - `line` - The line number, starting at 1.
+    ///
+    /// ## Returns
+    ///
+    /// The text of the line, or `None` if the file does not have that line.
+    pub fn line(&self, line: usize) -> Option<&str> {
+        let start: usize = *self.line_starts.get(line.checked_sub(1)?)?;
+        let end: usize = self.line_starts.get(line).map_or(self.content.len(), |next: &usize| next - 1);
+
+        Some(self.content[start..end].trim_end_matches('\r'))
+    }
+}
\ No newline at end of file
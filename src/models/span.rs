@@ -0,0 +1,54 @@
+use std::fmt;
+use std::sync::Arc;
+
+/// Represents a location in a source file, pointing at a token of `len` characters
+/// starting at `line` and `column` (both 1-based).
+#[derive(Debug, PartialEq, Clone, Default)]
+pub struct Span {
+    file: Arc<str>,
+    line: usize,
+    column: usize,
+    len: usize,
+}
+
+impl Span {
+    /// Creates a new `Span` with the specified file, line, column and length.
+    ///
+    /// ## Arguments
+    ///
+    /// - `file` - The path of the source file.
+    /// - `line` - The line of the token, starting at 1.
+    /// - `column` - The column of the token, starting at 1.
+    /// - `len` - The number of characters of the token.
+    ///
+    /// ## Returns
+    ///
+    /// A new `Span` instance.
+    pub fn new(file: &str, line: usize, column: usize, len: usize) -> Span {
+        Span { file: Arc::from(file), line, column, len }
+    }
+
+    /// Creates a `Span` that points to a whole file instead of a token.
+    pub fn file_only(file: &str) -> Span {
+        Span { file: Arc::from(file), line: 0, column: 0, len: 0 }
+    }
+
+    pub fn file(&self) -> &str { &self.file }
+    pub fn line(&self) -> usize { self.line }
+    pub fn column(&self) -> usize { self.column }
+    pub fn len(&self) -> usize { self.len }
+    pub fn is_empty(&self) -> bool { self.len == 0 }
+
+    /// Returns `true` if the span does not point to any file.
+    pub fn is_unknown(&self) -> bool { self.file.is_empty() && self.line == 0 }
+}
+
+impl fmt::Display for Span {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.line == 0 {
+            write!(f, "{}", self.file)
+        } else {
+            write!(f, "{}:{}:{}", self.file, self.line, self.column)
+        }
+    }
+}
@@ -0,0 +1,198 @@
+use crate::structs::{literal::Literal, expression::Expression};
+use crate::errors::error::SicompilerError;
+use super::{program::Program, span::Span};
+
+/// The kind of value a symbol names.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum SymbolKind {
+    /// The address of an instruction.
+    Label,
+    /// The address of a variable.
+    Variable,
+    /// The value of a constant.
+    Constant,
+}
+
+/// Represents a name that can be written instead of an operand.
+#[derive(Debug, PartialEq, Clone)]
+pub struct Symbol {
+    name: String,
+    kind: SymbolKind,
+    value: u64,
+    span: Span,
+}
+
+impl Symbol {
+    /// Creates a new `Symbol` with the specified name, kind, value and location.
+    ///
+    /// ## Arguments
+    ///
+    /// - `name` - The name of the symbol.
+    /// - `kind` - What the symbol names.
+    /// - `value` - The value the symbol is resolved to.
+    /// - `span` - The location where the symbol is defined.
+    ///
+    /// ## Returns
+    ///
+    /// A new `Symbol` instance.
+    pub fn new(name: &str, kind: SymbolKind, value: u64, span: Span) -> Symbol {
+        Symbol { name: name.to_string(), kind, value, span }
+    }
+
+    pub fn name(&self) -> &str { &self.name }
+    pub fn kind(&self) -> SymbolKind { self.kind }
+    pub fn value(&self) -> u64 { self.value }
+    pub fn span(&self) -> &Span { &self.span }
+}
+
+/// Represents the symbols defined in a program, in the order they are defined.
+#[derive(Debug, Default)]
+pub struct SymbolTable {
+    symbols: Vec<Symbol>,
+}
+
+impl SymbolTable {
+    pub fn new() -> SymbolTable {
+        SymbolTable { symbols: Vec::new() }
+    }
+
+    /// Builds the symbol table of a program: the named variables followed by the labels and the constants.
+    /// Variables are resolved to their dir, labels from the init address and constants to the value of
+    /// their expression. Only the first definition of a duplicated name is kept.
+    pub fn from_program(program: &Program) -> SymbolTable {
+        SymbolTable::from_program_checked(program).0
+    }
+
+    /// Builds the symbol table of a program as `from_program` does, keeping the errors of the constants.
+    ///
+    /// The constants are evaluated in the order they are defined, so the value of a constant can name
+    /// the variables, the labels and the constants defined before it. In a constant, `$` is the address
+    /// of the next instruction, which is the init address for the constants of the variables section.
+    /// A constant whose value can not be evaluated is left out of the table.
+    ///
+    /// ## Returns
+    ///
+    /// The symbol table and the errors of the constants that could not be evaluated.
+    pub fn from_program_checked(program: &Program) -> (SymbolTable, Vec<SicompilerError>) {
+        let mut table: SymbolTable = SymbolTable::new();
+        let mut errors: Vec<SicompilerError> = Vec::new();
+        let init: u64 = Literal::parse(program.init().dir()).unwrap_or_default();
+
+        for variable in program.variables() {
+            let Some(name) = variable.symbol() else { continue };
+            let dir: u64 = Literal::parse(variable.dir()).unwrap_or_default();
+
+            let _ = table.insert(Symbol::new(name, SymbolKind::Variable, dir, variable.symbol_span().clone()));
+        }
+
+        for label in program.labels() {
+            let _ = table.insert(Symbol::new(label.name(), SymbolKind::Label, init + label.index() as u64, label.span().clone()));
+        }
+
+        for constant in program.constants() {
+            match Expression::evaluate(constant.value(), &table, init + constant.index() as u64, constant.value_span()) {
+                Ok(value) => { let _ = table.insert(Symbol::new(constant.name(), SymbolKind::Constant, value, constant.span().clone())); },
+                Err(err) => errors.push(err)
+            }
+        }
+
+        (table, errors)
+    }
+
+    pub fn symbols(&self) -> &Vec<Symbol> { &self.symbols }
+
+    /// Returns the symbol with the specified name, if it is defined.
+    pub fn get(&self, name: &str) -> Option<&Symbol> {
+        self.symbols.iter().find(|symbol: &&Symbol| symbol.name() == name)
+    }
+
+    /// Adds a symbol to the table.
+    ///
+    /// ## Returns
+    ///
+    /// The symbol already defined with the same name, if there is one, in which case the table is not changed.
+    pub fn insert(&mut self, symbol: Symbol) -> Result<(), &Symbol> {
+        match self.symbols.iter().position(|defined: &Symbol| defined.name() == symbol.name()) {
+            Some(index) => Err(&self.symbols[index]),
+            None => {
+                self.symbols.push(symbol);
+                Ok(())
+            }
+        }
+    }
+
+    /// Resolves an operand: the value of the symbol it names, or the operand itself read as a number.
+    pub fn resolve(&self, operand: &str) -> Option<u64> {
+        match self.get(operand) {
+            Some(symbol) => Some(symbol.value()),
+            None => Literal::parse(operand)
+        }
+    }
+
+    /// Checks whether a name can be used as a symbol: it starts with a letter or `_`, it only has
+    /// letters, digits and `_`, and it can not be read as a hex number.
+    pub fn is_valid_name(name: &str) -> bool {
+        name.chars().next().is_some_and(|first: char| first.is_ascii_alphabetic() || first == '_')
+            && name.chars().all(|c: char| c.is_ascii_alphanumeric() || c == '_')
+            && !name.chars().all(|c: char| c.is_ascii_hexdigit())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::models::{init::Init, instruction::Instruction, label::Label, variable::Variable, constant::Constant};
+
+    use super::*;
+
+    #[test]
+    fn test_from_program() {
+        let mut variable: Variable = Variable::new("4", "0003");
+        variable.set_symbol("count", Span::default());
+
+        let mut program: Program = Program::new(vec![variable, Variable::new("5", "0000")], Init::new("10"), vec![Instruction::new("HALT", vec![])]);
+        program.set_labels(vec![Label::new("START", 0), Label::new("END", 1), Label::new("START", 1), Label::new("count", 0)]);
+
+        let table: SymbolTable = SymbolTable::from_program(&program);
+
+        assert_eq!(table.symbols().len(), 3);
+        assert_eq!(table.get("count").unwrap().value(), 4);
+        assert_eq!(table.get("count").unwrap().kind(), SymbolKind::Variable);
+        assert_eq!(table.get("START").unwrap().value(), 0x10);
+        assert_eq!(table.get("END").unwrap().value(), 0x11);
+        assert_eq!(table.resolve("END"), Some(0x11));
+        assert_eq!(table.resolve("1F"), Some(0x1F));
+        assert_eq!(table.resolve("LOOP"), None);
+    }
+
+    #[test]
+    fn test_from_program_checked() {
+        let mut program: Program = Program::new(vec![], Init::new("10"), vec![Instruction::new("ADD", vec!["1"]), Instruction::new("HALT", vec![])]);
+        program.set_constants(vec![
+            Constant::new("START", "$", 0),
+            Constant::new("HERE", "$", 1),
+            Constant::new("NEXT", "HERE + 1", 1),
+            Constant::new("BAD", "LATER", 1),
+            Constant::new("LATER", "1", 2)
+        ]);
+
+        let (table, errors): (SymbolTable, Vec<SicompilerError>) = SymbolTable::from_program_checked(&program);
+
+        assert_eq!(table.get("START").unwrap().value(), 0x10);
+        assert_eq!(table.get("HERE").unwrap().value(), 0x11);
+        assert_eq!(table.get("NEXT").unwrap().value(), 0x12);
+        assert_eq!(table.get("NEXT").unwrap().kind(), SymbolKind::Constant);
+        assert!(table.get("BAD").is_none());
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].to_string(), "error[SC0023]: The symbol 'LATER' is not defined");
+    }
+
+    #[test]
+    fn test_is_valid_name() {
+        assert!(SymbolTable::is_valid_name("LOOP"));
+        assert!(SymbolTable::is_valid_name("_end2"));
+        assert!(!SymbolTable::is_valid_name("2LOOP"));
+        assert!(!SymbolTable::is_valid_name("FACE"));
+        assert!(!SymbolTable::is_valid_name("MY-LOOP"));
+        assert!(!SymbolTable::is_valid_name(""));
+    }
+}
\ No newline at end of file
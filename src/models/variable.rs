@@ -1,25 +1,54 @@
-/// Represents a variable in a program with a directory and a name.
-#[derive(Debug)]
+use super::span::Span;
+
+/// Represents a variable in a program with a directory, an initial value and an optional name.
+#[derive(Debug, Clone)]
 pub struct Variable {
     dir: String,
-    name: String,
+    value: String,
+    symbol: Option<String>,
+    dir_span: Span,
+    value_span: Span,
+    symbol_span: Span,
 }
 
 impl Variable {
-    /// Creates a new `Variable` with the specified directory and name.
+    /// Creates a new `Variable` with the specified directory and initial value.
     ///
     /// ## Arguments
     ///
     /// - `dir` - The directory of the variable.
-    /// - `name` - The name of the variable.
+    /// - `value` - The initial value of the variable.
     ///
     /// ## Returns
     ///
     /// A new `Variable` instance.
-    pub fn new(dir: &str, name: &str) -> Variable {
-        Variable { dir: dir.to_string(), name: name.to_string() }
+    pub fn new(dir: &str, value: &str) -> Variable {
+        Variable {
+            dir: dir.to_string(),
+            value: value.to_string(),
+            symbol: None,
+            dir_span: Span::default(),
+            value_span: Span::default(),
+            symbol_span: Span::default()
+        }
     }
 
     pub fn dir(&self) -> &str { &self.dir }
-    pub fn name(&self) -> &str { &self.name }
+    pub fn value(&self) -> &str { &self.value }
+    pub fn dir_span(&self) -> &Span { &self.dir_span }
+    pub fn value_span(&self) -> &Span { &self.value_span }
+
+    /// The name the instructions can use instead of the directory, if the variable has one.
+    pub fn symbol(&self) -> Option<&str> { self.symbol.as_deref() }
+    pub fn symbol_span(&self) -> &Span { &self.symbol_span }
+
+    pub fn set_spans(&mut self, dir_span: Span, value_span: Span) {
+        self.dir_span = dir_span;
+        self.value_span = value_span;
+    }
+
+    pub fn set_symbol(&mut self, symbol: &str, span: Span) {
+        self.symbol = Some(symbol.to_string());
+        self.symbol_span = span;
+    }
 }
\ No newline at end of file
@@ -0,0 +1,394 @@
+use std::collections::HashMap;
+
+use crate::models::{
+    program::Program,
+    repertoire::Repertoire,
+    span::Span,
+    image::MemoryImage,
+    symbol::SymbolTable,
+    instruction::Instruction,
+    variable::Variable,
+    operand::OperandKind,
+    machine::Machine,
+    microprogram::ControlWord
+};
+use crate::errors::error::SicompilerError;
+use super::{literal::Literal, expression::Expression};
+
+/// The number of bits of the opcode of an instruction, enough for every instruction of a repertoire.
+pub const OPCODE_BITS: u32 = 5;
+
+/// The number of bits of a memory address, and of the operand field of an instruction, unless the repertoire changes it.
+pub const ADDRESS_BITS: u32 = 12;
+
+/// The largest number of address bits, so the byte address of every cell fits in the 16-bit
+/// addresses of the Intel HEX and S-record formats.
+pub const MAX_ADDRESS_BITS: u32 = 14;
+
+/// The number of bits of a register operand when its signature does not give them, enough for 16 registers.
+pub const REGISTER_BITS: u32 = 4;
+
+/// The number of bits of the data words, the width of ACC, unless the repertoire changes it.
+pub const DATA_BITS: u32 = 16;
+
+/// The largest number of data bits.
+pub const MAX_DATA_BITS: u32 = 32;
+
+/// The number of bits of an encoded instruction with the default widths, its opcode followed by its operand.
+pub const WORD_BITS: u32 = OPCODE_BITS + ADDRESS_BITS;
+
+/// The number of instructions a repertoire can have, one per opcode.
+pub const MAX_INSTRUCTIONS: usize = 1 << OPCODE_BITS;
+
+/// The `Assembler` struct translates a validated program into the memory image the SiCoMe machine runs.
+///
+/// Each mnemonic gets as opcode its position in the instructions section of the repertoire, and
+/// each instruction is encoded as its opcode followed by its operand field: `opcode << address_bits | operand`,
+/// with the address width of the `Machine` of the repertoire.
+/// Variables are stored at their dir and the instructions one after another from the init address.
+pub struct Assembler;
+
+impl Assembler {
+    /// Parses an address, checking it is inside the memory.
+    ///
+    /// ## Arguments
+    /// - `value` - The address, in any of the bases a `Literal` can be written in
+    /// - `machine` - The machine, which gives the size of the memory
+    /// - `span` - The location of the address, used to report it
+    ///
+    /// ## Returns
+    /// The address or an Error.
+    ///
+    pub(crate) fn parse_address(value: &str, machine: &Machine, span: &Span) -> Result<u64, SicompilerError> {
+        let out_of_range = || SicompilerError::AddressOutOfRange { address: value.to_string(), max: machine.address_max(), span: span.clone() };
+
+        Literal::parse(value)
+            .filter(|address: &u64| *address <= machine.address_max())
+            .ok_or_else(out_of_range)
+    }
+
+    /// Parses a data word, checking it fits in the data bits.
+    ///
+    /// ## Arguments
+    /// - `value` - The word, a number in any of the bases a `Literal` can be written in or an `Expression`
+    /// - `symbols` - The labels and variables the expression can name
+    /// - `dir` - The address the word is stored at, the value of `$`
+    /// - `machine` - The machine, which gives the width of the data words
+    /// - `span` - The location of the word, used to report it
+    ///
+    /// ## Returns
+    /// The word or an Error.
+    ///
+    pub(crate) fn parse_data(value: &str, symbols: &SymbolTable, dir: u64, machine: &Machine, span: &Span) -> Result<u64, SicompilerError> {
+        let out_of_range = || SicompilerError::ValueOutOfRange { value: value.to_string(), bits: machine.data_bits(), max: machine.data_max(), span: span.clone() };
+
+        //* A number too large for 64 bits is reported as out of range instead of as an overflow
+        let word: Option<u64> = match Literal::is_valid(value) {
+            true => Literal::parse(value),
+            false => Some(Expression::evaluate(value, symbols, dir, span)?)
+        };
+
+        word.filter(|word: &u64| *word <= machine.data_max()).ok_or_else(out_of_range)
+    }
+
+    /// Parses a register operand, `R` followed by its number in decimal, e.g. `R3`.
+    pub(crate) fn parse_register(param: &str) -> Option<u64> {
+        param.strip_prefix(['R', 'r'])
+            .filter(|number: &&str| !number.is_empty() && number.chars().all(|c: char| c.is_ascii_digit()))
+            .and_then(|number: &str| number.parse::<u64>().ok())
+    }
+
+    /// Returns the value of an operand of the specified kind: the number of a register, or the value of
+    /// a number, a label, a variable or an expression for the other kinds, where `$` is `address`.
+    /// `None` if it is not of that kind or its expression can not be evaluated.
+    pub(crate) fn operand_value(param: &str, kind: OperandKind, symbols: &SymbolTable, address: u64) -> Option<u64> {
+        match kind {
+            OperandKind::Register => Assembler::parse_register(param),
+            _ => Expression::evaluate(param, symbols, address, &Span::default()).ok()
+        }
+    }
+
+    /// Encodes the operands of an instruction in the operand field, packed as its repertoire entry
+    /// declares them, the first one in the highest bits.
+    ///
+    /// ## Arguments
+    /// - `instruction` - The instruction of the program
+    /// - `entry` - The repertoire instruction, with the signature of the operands
+    /// - `symbols` - The labels and variables the operands can name
+    /// - `address` - The address of the instruction, the value of `$`
+    ///
+    /// ## Returns
+    /// The operand field or an Error.
+    ///
+    pub(crate) fn encode_operands(instruction: &Instruction, entry: &Instruction, symbols: &SymbolTable, address: u64) -> Result<u64, SicompilerError> {
+        let mut field: u64 = 0;
+
+        for (index, (param, operand)) in instruction.params().iter().zip(entry.operands()).enumerate() {
+            let span: &Span = instruction.param_span(index);
+
+            if operand.kind() != OperandKind::Register && Assembler::parse_register(param).is_none() {
+                Expression::evaluate(param, symbols, address, span)?;
+            }
+
+            let value: u64 = Assembler::operand_value(param, operand.kind(), symbols, address).ok_or_else(|| SicompilerError::WrongOperandKind {
+                mnemonic: instruction.mnemonic().to_string(),
+                operand: param.clone(),
+                expected: operand.kind().name().to_string(),
+                span: span.clone(),
+                entry: entry.span().clone()
+            })?;
+
+            if value > operand.max() {
+                return Err(SicompilerError::OperandOutOfRange {
+                    mnemonic: instruction.mnemonic().to_string(),
+                    operand: param.clone(),
+                    width: operand.width(),
+                    span: span.clone(),
+                    entry: entry.span().clone()
+                });
+            }
+
+            field = field << operand.width() | value;
+        }
+
+        Ok(field)
+    }
+
+    /// Finds the variables and the instructions placed at an address another one already takes, so
+    /// none of them is silently overwritten: two variables with the same dir, or an instruction over a variable.
+    ///
+    /// The dirs that are not valid addresses, and the instructions out of the memory, are left to the other checks.
+    ///
+    /// ## Arguments
+    ///
+    /// - `program` - The program to lay out.
+    /// - `machine` - The machine, which gives the size of the memory.
+    ///
+    /// ## Returns
+    ///
+    /// An `Error` for every variable or instruction placed at a taken address, pointing to it and to the first one placed there.
+    ///
+    pub(crate) fn overlaps(program: &Program, machine: &Machine) -> Vec<SicompilerError> {
+        let mut taken: HashMap<u64, &Span> = HashMap::new();
+        let mut errors: Vec<SicompilerError> = Vec::new();
+
+        let variables = program.variables().iter()
+            .filter_map(|variable: &Variable| Literal::parse(variable.dir()).map(|dir: u64| (dir, variable.dir_span())));
+
+        let instructions = Literal::parse(program.init().dir()).into_iter()
+            .flat_map(|init: u64| (init..).zip(program.instructions()))
+            .map(|(address, instruction): (u64, &Instruction)| (address, instruction.span()));
+
+        for (address, span) in variables.chain(instructions) {
+            if address > machine.address_max() { continue }
+
+            match taken.get(&address) {
+                Some(first) => errors.push(SicompilerError::OverlappingAddress {
+                    address: format!("{:X}", address),
+                    first: (*first).clone(),
+                    span: span.clone()
+                }),
+                None => { taken.insert(address, span); }
+            }
+        }
+
+        errors
+    }
+
+    /// Assembles a program, encoding its instructions and placing them and its variables in memory.
+    ///
+    /// ## Arguments
+    ///
+    /// - `program` - The validated program to assemble.
+    /// - `repertoire` - The repertoire the program was validated against, which gives the opcodes.
+    ///
+    /// ## Returns
+    ///
+    /// - `Result<MemoryImage, Error>` - The memory image, or an `Error` if the program does not fit in memory
+    ///   or places two words at the same address.
+    ///
+    pub fn assemble(program: &Program, repertoire: &Repertoire) -> Result<MemoryImage, SicompilerError> {
+        let machine: &Machine = repertoire.machine();
+
+        if let Some(err) = Assembler::overlaps(program, machine).into_iter().next() {
+            return Err(err);
+        }
+
+        let mut image: MemoryImage = MemoryImage::new(machine.memory_size(), machine.word_bits());
+
+        let symbols: SymbolTable = SymbolTable::from_program(program);
+
+        for variable in program.variables() {
+            let dir: u64 = Assembler::parse_address(variable.dir(), machine, variable.dir_span())?;
+            let value: u64 = Assembler::parse_data(variable.value(), &symbols, dir, machine, variable.value_span())?;
+
+            image.set(dir, value);
+        }
+
+        let init: u64 = Assembler::parse_address(program.init().dir(), machine, program.init().span())?;
+
+        for (index, instruction) in program.instructions().iter().enumerate() {
+            let address: u64 = init + index as u64;
+
+            if address > machine.address_max() {
+                return Err(SicompilerError::AddressOutOfRange {
+                    address: format!("{:X}", address),
+                    max: machine.address_max(),
+                    span: instruction.span().clone()
+                });
+            }
+
+            let opcode: usize = repertoire.opcode(instruction.mnemonic()).ok_or_else(|| SicompilerError::UnknownMnemonic {
+                mnemonic: instruction.mnemonic().to_string(),
+                span: instruction.span().clone()
+            })?;
+
+            let operand: u64 = Assembler::encode_operands(instruction, &repertoire.instructions()[opcode], &symbols, address)?;
+
+            image.set(address, machine.encode(opcode, operand));
+        }
+
+        Ok(image)
+    }
+
+    /// Lays out the microcode of a repertoire in a control ROM.
+    ///
+    /// The ROM is divided into rows of the same power-of-two size, enough for the longest microprogram:
+    /// the fetch cycle is the row `0` and the execute cycle of each opcode is the row `opcode + 1`, so
+    /// the control word of each microstep is at `row << step_bits | microstep`.
+    ///
+    /// ## Arguments
+    ///
+    /// - `repertoire` - The repertoire with the microcode.
+    /// - `control_width` - The number of control signals of each control word.
+    ///
+    /// ## Returns
+    ///
+    /// The control ROM image.
+    ///
+    pub fn assemble_microcode(repertoire: &Repertoire, control_width: u32) -> MemoryImage {
+        let rows: Vec<&Vec<ControlWord>> = std::iter::once(repertoire.microprogram().fetch())
+            .chain(repertoire.instructions().iter().map(|instruction: &Instruction| instruction.microcode()))
+            .collect();
+
+        let row_size: usize = rows.iter().map(|row: &&Vec<ControlWord>| row.len()).max().unwrap_or_default().next_power_of_two();
+        let mut image: MemoryImage = MemoryImage::new(rows.len() * row_size, control_width);
+
+        for (row, words) in rows.iter().enumerate() {
+            for (microstep, word) in words.iter().enumerate() {
+                image.set((row * row_size + microstep) as u64, word.value());
+            }
+        }
+
+        image
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::models::{
+        init::Init,
+        variable::Variable,
+        instruction::Instruction,
+        program::Program,
+        label::Label,
+        microprogram::Microprogram,
+        operand::Operand
+    };
+
+    use crate::structs::fixtures::{entry, fetch};
+
+    use super::*;
+
+    fn repertoire() -> Repertoire {
+        Repertoire::new(fetch(), vec![Instruction::new("HALT", vec![]), entry("ADD"), entry("JMP")])
+    }
+
+    #[test]
+    fn test_assemble() {
+        let mut program: Program = Program::new(
+            vec![Variable::new("0", "0003"), Variable::new("A", "FFFF")],
+            Init::new("6"),
+            vec![
+                Instruction::new("ADD", vec!["A"]),
+                Instruction::new("JMP", vec!["END"]),
+                Instruction::new("HALT", vec![])
+            ]
+        );
+        program.set_labels(vec![Label::new("END", 2)]);
+
+        let image: MemoryImage = Assembler::assemble(&program, &repertoire()).unwrap();
+
+        assert_eq!(image.word_bits(), WORD_BITS);
+        assert_eq!(image.get(0), 3);
+        assert_eq!(image.get(0xA), 0xFFFF);
+        assert_eq!(image.get(6), 1 << ADDRESS_BITS | 0xA);
+        assert_eq!(image.get(7), 2 << ADDRESS_BITS | 8);
+        assert_eq!(image.get(8), 0);
+        assert!(image.is_used(8));
+        assert_eq!(image.used_len(), 0xB);
+    }
+
+    #[test]
+    fn test_assemble_operands() {
+        let mut ldi: Instruction = Instruction::new("LDI", vec![]);
+        ldi.set_operands(vec![Operand::new(OperandKind::Register, 4), Operand::new(OperandKind::Immediate, 8)]);
+
+        let repertoire: Repertoire = Repertoire::new(Microprogram::new(vec![]), vec![Instruction::new("HALT", vec![]), ldi]);
+        let program: Program = Program::new(vec![], Init::new("0"), vec![Instruction::new("LDI", vec!["R3", "2A"])]);
+        let image: MemoryImage = Assembler::assemble(&program, &repertoire).unwrap();
+
+        assert_eq!(image.get(0), 1 << ADDRESS_BITS | 0x32A);
+
+        let program: Program = Program::new(vec![], Init::new("0"), vec![Instruction::new("LDI", vec!["R3", "12A"])]);
+        let result: Result<MemoryImage, SicompilerError> = Assembler::assemble(&program, &repertoire);
+
+        assert_eq!(result.unwrap_err().to_string(), "error[SC0035]: The parameter '12A' in 'LDI' does not fit in 8 bits");
+    }
+
+    #[test]
+    fn test_assemble_errors() {
+        let program: Program = Program::new(vec![], Init::new("FFF"), vec![Instruction::new("HALT", vec![]), Instruction::new("HALT", vec![])]);
+        let result: Result<MemoryImage, SicompilerError> = Assembler::assemble(&program, &repertoire());
+
+        assert_eq!(result.unwrap_err().to_string(), "error[SC0019]: The address '1000' is out of the memory, the max is 'FFF'");
+
+        let program: Program = Program::new(vec![], Init::new("0"), vec![Instruction::new("SUB", vec!["1"])]);
+        let result: Result<MemoryImage, SicompilerError> = Assembler::assemble(&program, &repertoire());
+
+        assert!(result.unwrap_err().to_string().starts_with("error[SC0013]"));
+
+        let program: Program = Program::new(vec![Variable::new("A", "1FFFF")], Init::new("0"), vec![Instruction::new("HALT", vec![])]);
+        let result: Result<MemoryImage, SicompilerError> = Assembler::assemble(&program, &repertoire());
+
+        assert_eq!(result.unwrap_err().to_string(), "error[SC0036]: The value '1FFFF' does not fit in 16 data bits, the max is 'FFFF'");
+
+        let program: Program = Program::new(vec![Variable::new("7", "5")], Init::new("6"), vec![Instruction::new("ADD", vec!["7"]), Instruction::new("HALT", vec![])]);
+        let result: Result<MemoryImage, SicompilerError> = Assembler::assemble(&program, &repertoire());
+
+        assert_eq!(result.unwrap_err().to_string(), "error[SC0043]: The address '7' is already taken by another variable or instruction");
+    }
+
+    #[test]
+    fn test_assemble_microcode() {
+        let words = |values: &[u64]| values.iter().map(|value: &u64| ControlWord::new(*value, Span::default())).collect::<Vec<ControlWord>>();
+
+        let mut halt: Instruction = Instruction::new("HALT", vec![]);
+        halt.set_microcode(words(&[0]));
+
+        let mut add: Instruction = Instruction::new("ADD", vec![]);
+        add.set_microcode(words(&[0x8000100, 0x1100, 0x28200]));
+
+        let repertoire: Repertoire = Repertoire::new(Microprogram::new(words(&[0x4000100, 0x0201100, 0x3000300])), vec![halt, add]);
+        let image: MemoryImage = Assembler::assemble_microcode(&repertoire, 28);
+
+        assert_eq!(image.word_bits(), 28);
+        assert_eq!(image.words().len(), 12);
+        assert_eq!(image.words()[..3], [0x4000100, 0x0201100, 0x3000300]);
+        assert!(!image.is_used(3));
+        assert!(image.is_used(4));
+        assert_eq!(image.get(4), 0);
+        assert_eq!(image.words()[8..11], [0x8000100, 0x1100, 0x28200]);
+        assert_eq!(image.used_len(), 11);
+    }
+}
\ No newline at end of file
@@ -0,0 +1,457 @@
+use std::io::{self, BufRead, Write};
+
+use crate::models::{
+    program::Program,
+    repertoire::Repertoire,
+    instruction::Instruction,
+    variable::Variable,
+    span::Span,
+    symbol::{Symbol, SymbolKind, SymbolTable}
+};
+use crate::errors::error::SicompilerError;
+use super::{simulator::{Simulator, Phase}, literal::Literal};
+
+/// The number of microsteps the debugger records, so they can be undone.
+pub const DEFAULT_HISTORY: usize = 100_000;
+
+const HELP: &str = "\
+Commands:
+  step [N]          (s)    Run N instructions, 1 by default
+  microstep [N]     (ms)   Run N microsteps, 1 by default
+  reverse [N]       (rs)   Undo N instructions, 1 by default
+  reverse-micro [N] (rms)  Undo N microsteps, 1 by default
+  continue          (c)    Run until a breakpoint, a watchpoint or the end of the program
+  break <ADDR>      (b)    Stop before running the instruction at ADDR, in hex or a label
+  break :<LINE>            Stop before running the instruction written at LINE
+  watch <ADDR>      (w)    Stop when the variable at ADDR changes, in hex or its name
+  delete <ADDR>     (d)    Remove the breakpoint and the watchpoint at ADDR
+  regs              (r)    Print the registers
+  mem <ADDR> [N]    (m)    Print N memory cells from ADDR, 1 by default
+  where                    Print the instruction that runs next
+  help              (h)    Print this message
+  quit              (q)    Exit the debugger";
+
+/// How many steps a run should take before stopping.
+#[derive(Debug, PartialEq, Clone, Copy)]
+enum Unit {
+    Microstep,
+    Instruction,
+    Continue,
+}
+
+/// Why a run stopped.
+#[derive(Debug)]
+enum Stop {
+    Done,
+    Halted,
+    Breakpoint(u64),
+    Watchpoint(u64, u64, u64),
+    Error(SicompilerError),
+}
+
+/// The `Debugger` struct runs a program in a `Simulator` following the commands of the user,
+/// stopping at breakpoints and watchpoints and undoing steps with the recorded history.
+pub struct Debugger {
+    simulator: Simulator,
+    listing: Vec<(u64, String, Span)>,
+    variables: Vec<u64>,
+    symbols: SymbolTable,
+    breakpoints: Vec<u64>,
+    watchpoints: Vec<u64>,
+}
+
+impl Debugger {
+    /// Creates a new `Debugger` with the program loaded and stopped before its first instruction.
+    ///
+    /// ## Arguments
+    ///
+    /// - `program` - The validated program to debug.
+    /// - `repertoire` - The repertoire the program was validated against.
+    ///
+    /// ## Returns
+    ///
+    /// - `Result<Debugger, Error>` - The debugger, or an `Error` if the program does not fit in memory.
+    ///
+    pub fn new(program: &Program, repertoire: &Repertoire) -> Result<Debugger, SicompilerError> {
+        let mut simulator: Simulator = Simulator::new(program, repertoire)?;
+        simulator.set_history_limit(DEFAULT_HISTORY);
+
+        let listing: Vec<(u64, String, Span)> = program.instructions().iter().enumerate().map(|(index, instruction): (usize, &Instruction)| {
+            let text: String = [instruction.mnemonic().to_string()].iter().chain(instruction.params()).cloned().collect::<Vec<String>>().join(" ");
+            (simulator.pc() + index as u64, text, instruction.span().clone())
+        }).collect();
+
+        let variables: Vec<u64> = program.variables().iter()
+            .filter_map(|variable: &Variable| Literal::parse(variable.dir()))
+            .collect();
+
+        Ok(Debugger {
+            simulator,
+            listing,
+            variables,
+            symbols: SymbolTable::from_program(program),
+            breakpoints: Vec::new(),
+            watchpoints: Vec::new()
+        })
+    }
+
+    pub fn simulator(&self) -> &Simulator { &self.simulator }
+    pub fn breakpoints(&self) -> &Vec<u64> { &self.breakpoints }
+    pub fn watchpoints(&self) -> &Vec<u64> { &self.watchpoints }
+
+    /// Checks whether the simulator is about to fetch a new instruction.
+    fn at_boundary(&self) -> bool {
+        self.simulator.phase() == Phase::Fetch && self.simulator.microstep() == 0
+    }
+
+    /// Runs microsteps until `count` units are done, the machine halts, or a breakpoint or watchpoint is hit.
+    ///
+    /// ## Arguments
+    /// - `unit` - What is counted
+    /// - `count` - How many units to run, ignored when continuing
+    ///
+    /// ## Returns
+    /// The reason the run stopped.
+    ///
+    fn advance(&mut self, unit: Unit, count: usize) -> Stop {
+        let mut done: usize = 0;
+
+        loop {
+            if self.simulator.halted() {
+                return Stop::Halted;
+            }
+
+            let before: Vec<u64> = self.watchpoints.iter().map(|address: &u64| self.simulator.memory()[*address as usize]).collect();
+
+            if let Err(err) = self.simulator.micro_step() {
+                return Stop::Error(err);
+            }
+
+            for (address, old) in self.watchpoints.iter().zip(before) {
+                let new: u64 = self.simulator.memory()[*address as usize];
+
+                if new != old {
+                    return Stop::Watchpoint(*address, old, new);
+                }
+            }
+
+            if self.simulator.halted() {
+                return Stop::Halted;
+            }
+
+            let boundary: bool = self.at_boundary();
+
+            if unit == Unit::Microstep || (unit == Unit::Instruction && boundary) {
+                done += 1;
+            }
+
+            if unit != Unit::Continue && done == count {
+                return Stop::Done;
+            }
+
+            if boundary && self.breakpoints.contains(&self.simulator.address()) {
+                return Stop::Breakpoint(self.simulator.address());
+            }
+        }
+    }
+
+    /// Undoes `count` units, stopping early if the history runs out.
+    ///
+    /// ## Returns
+    /// `false` if the history ran out.
+    ///
+    fn reverse(&mut self, unit: Unit, count: usize) -> bool {
+        for _ in 0..count {
+            if !self.simulator.back() {
+                return false;
+            }
+
+            while unit == Unit::Instruction && !self.at_boundary() {
+                if !self.simulator.back() {
+                    return false;
+                }
+            }
+        }
+
+        true
+    }
+
+    /// Parses an address given to a command, in hex base or as the name of a label or a variable.
+    fn parse_address(&self, value: Option<&str>) -> Result<u64, String> {
+        let value: &str = value.ok_or("missing address")?;
+
+        self.symbols.resolve(value)
+            .filter(|address: &u64| (*address as usize) < self.simulator.memory().len())
+            .ok_or(format!("'{}' is not a valid address", value))
+    }
+
+    /// Parses the optional count given to a command.
+    fn parse_count(value: Option<&str>) -> Result<usize, String> {
+        match value {
+            Some(value) => value.parse::<usize>().ok().filter(|count: &usize| *count > 0).ok_or(format!("'{}' is not a valid count", value)),
+            None => Ok(1)
+        }
+    }
+
+    /// Describes the instruction that runs next, with its location in the source file.
+    fn location(&self) -> String {
+        if self.simulator.halted() {
+            return format!("halted at {:03X}", self.simulator.address());
+        }
+
+        let address: u64 = self.simulator.address();
+        let mut location: String = match self.listing.iter().find(|(line_address, _, _): &&(u64, String, Span)| *line_address == address) {
+            Some((_, text, span)) if span.is_unknown() => format!("{:03X}: {}", address, text),
+            Some((_, text, span)) => format!("{:03X}: {}  ({})", address, text, span),
+            None => format!("{:03X}: {:05X}", address, self.simulator.memory()[address as usize])
+        };
+
+        if !self.at_boundary() {
+            let phase: String = match self.simulator.phase() {
+                Phase::Fetch => "fetch".to_string(),
+                Phase::Execute(opcode) => format!("execute {}", self.simulator.mnemonic(opcode).unwrap_or_default())
+            };
+
+            location.push_str(&format!(" [{} microstep {}]", phase, self.simulator.microstep()));
+        }
+
+        location
+    }
+
+    /// Describes why a run stopped.
+    fn report(&self, stop: Stop) -> String {
+        match stop {
+            Stop::Done => self.location(),
+            Stop::Halted => format!("Program halted at {:03X}", self.simulator.address()),
+            Stop::Breakpoint(address) => format!("Breakpoint at {:03X}\n{}", address, self.location()),
+            Stop::Watchpoint(address, old, new) =>
+                format!("Watchpoint {:03X}: {:04X} -> {:04X}\n{}", address, old, new, self.location()),
+            Stop::Error(err) => err.to_string()
+        }
+    }
+
+    /// Runs a command, writing its result to `output`.
+    ///
+    /// ## Arguments
+    ///
+    /// - `command` - The command line written by the user.
+    /// - `output` - Where the result is written.
+    ///
+    /// ## Returns
+    ///
+    /// - `io::Result<bool>` - `false` if the user asked to quit, or an error if `output` could not be written.
+    ///
+    pub fn execute(&mut self, command: &str, output: &mut impl Write) -> io::Result<bool> {
+        let parts: Vec<&str> = command.split_whitespace().collect();
+        let Some(&name) = parts.first() else { return Ok(true) };
+        let argument: Option<&str> = parts.get(1).copied();
+
+        let result: Result<String, String> = match name {
+            "step" | "s" => Debugger::parse_count(argument).map(|count: usize| {
+                let stop: Stop = self.advance(Unit::Instruction, count);
+                self.report(stop)
+            }),
+            "microstep" | "ms" => Debugger::parse_count(argument).map(|count: usize| {
+                let stop: Stop = self.advance(Unit::Microstep, count);
+                self.report(stop)
+            }),
+            "continue" | "c" => {
+                let stop: Stop = self.advance(Unit::Continue, 0);
+                Ok(self.report(stop))
+            },
+            "reverse" | "rs" | "reverse-micro" | "rms" => Debugger::parse_count(argument).map(|count: usize| {
+                let unit: Unit = if name.starts_with("reverse-") || name == "rms" { Unit::Microstep } else { Unit::Instruction };
+
+                if self.reverse(unit, count) {
+                    self.location()
+                } else {
+                    format!("Reached the start of the history\n{}", self.location())
+                }
+            }),
+            "break" | "b" => match argument.and_then(|argument: &str| argument.strip_prefix(':')) {
+                Some(line) => line.parse::<usize>().ok()
+                    .and_then(|line: usize| self.listing.iter().find(|(_, _, span): &&(u64, String, Span)| span.line() == line))
+                    .map(|(address, _, _): &(u64, String, Span)| *address)
+                    .ok_or(format!("there is no instruction at line {}", line)),
+                None => self.parse_address(argument)
+            }.map(|address: u64| {
+                if !self.breakpoints.contains(&address) {
+                    self.breakpoints.push(address);
+                }
+                format!("Breakpoint at {:03X}", address)
+            }),
+            "watch" | "w" => self.parse_address(argument).and_then(|address: u64| {
+                if !self.variables.contains(&address) {
+                    return Err(format!("'{:X}' is not declared in the variables section", address));
+                }
+
+                if !self.watchpoints.contains(&address) {
+                    self.watchpoints.push(address);
+                }
+                Ok(format!("Watchpoint at {:03X} = {:04X}", address, self.simulator.memory()[address as usize]))
+            }),
+            "delete" | "d" => self.parse_address(argument).map(|address: u64| {
+                self.breakpoints.retain(|breakpoint: &u64| *breakpoint != address);
+                self.watchpoints.retain(|watchpoint: &u64| *watchpoint != address);
+                format!("Deleted the breakpoints and watchpoints at {:03X}", address)
+            }),
+            "regs" | "r" => Ok(self.simulator.registers()),
+            "mem" | "m" => self.parse_address(argument).and_then(|address: u64| {
+                let count: usize = Debugger::parse_count(parts.get(2).copied())?;
+
+                Ok(self.simulator.memory().iter().enumerate().skip(address as usize).take(count)
+                    .map(|(address, value): (usize, &u64)| {
+                        let name: Option<&Symbol> = self.symbols.symbols().iter()
+                            .find(|symbol: &&Symbol| symbol.kind() == SymbolKind::Variable && symbol.value() == address as u64);
+
+                        match name {
+                            Some(symbol) => format!("{:03X}: {:05X}  {}", address, value, symbol.name()),
+                            None => format!("{:03X}: {:05X}", address, value)
+                        }
+                    })
+                    .collect::<Vec<String>>()
+                    .join("\n"))
+            }),
+            "where" => Ok(self.location()),
+            "help" | "h" => Ok(HELP.to_string()),
+            "quit" | "q" => return Ok(false),
+            _ => Err(format!("unknown command '{}', write 'help' to see the commands", name))
+        };
+
+        match result {
+            Ok(message) => writeln!(output, "{}", message)?,
+            Err(message) => writeln!(output, "error: {}", message)?
+        }
+
+        Ok(true)
+    }
+
+    /// Reads commands from `input` until the user quits or the input ends.
+    ///
+    /// ## Arguments
+    ///
+    /// - `input` - Where the commands are read from, one per line.
+    /// - `output` - Where the prompt and the results are written.
+    ///
+    pub fn repl(&mut self, input: impl BufRead, mut output: impl Write) -> io::Result<()> {
+        writeln!(output, "{}", self.location())?;
+        write!(output, "(debug) ")?;
+        output.flush()?;
+
+        for line in input.lines() {
+            if !self.execute(&line?, &mut output)? {
+                return Ok(());
+            }
+
+            write!(output, "(debug) ")?;
+            output.flush()?;
+        }
+
+        writeln!(output)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::models::{
+        init::Init,
+        label::Label,
+    };
+    use crate::structs::fixtures::{fetch, microcoded};
+
+    use super::*;
+
+    fn debugger() -> Debugger {
+        let repertoire: Repertoire = Repertoire::new(fetch(), vec![
+            microcoded("HALT", false, &[0]),
+            microcoded("ADD", true, &[0x8000100, 0x1100, 0x28200]),
+            microcoded("STA", true, &[0x8000100, 0x2100, 0x1000200]),
+        ]);
+
+        let instructions: Vec<Instruction> = [("ADD", "0", 5), ("ADD", "0", 6), ("STA", "4", 7)].iter()
+            .map(|(mnemonic, param, line): &(&str, &str, usize)| {
+                let mut instruction: Instruction = Instruction::new(mnemonic, vec![param]);
+                instruction.set_flag(true);
+                instruction.set_span(Span::new("test.txt", *line, 1, 3));
+                instruction
+            })
+            .chain([Instruction::new("HALT", vec![])])
+            .collect();
+
+        let mut result: Variable = Variable::new("4", "0000");
+        result.set_symbol("result", Span::default());
+
+        let mut program: Program = Program::new(vec![Variable::new("0", "0003"), result], Init::new("6"), instructions);
+        program.set_labels(vec![Label::new("STORE", 2)]);
+
+        Debugger::new(&program, &repertoire).unwrap()
+    }
+
+    fn run(debugger: &mut Debugger, command: &str) -> String {
+        let mut output: Vec<u8> = Vec::new();
+        debugger.execute(command, &mut output).unwrap();
+        String::from_utf8(output).unwrap()
+    }
+
+    #[test]
+    fn test_step() {
+        let mut debugger: Debugger = debugger();
+
+        assert_eq!(run(&mut debugger, "step"), "007: ADD 0  (test.txt:6:1)\n");
+        assert_eq!(debugger.simulator().acc(), 3);
+        assert_eq!(run(&mut debugger, "ms 2"), "007: ADD 0  (test.txt:6:1) [fetch microstep 2]\n");
+        assert_eq!(run(&mut debugger, "regs"), "ACC=0003 F=0 Z=1 PC=008 MAR=007 MDR=01000 IR=01000 steps=8\n");
+        assert_eq!(run(&mut debugger, "s 5"), "Program halted at 009\n");
+        assert_eq!(run(&mut debugger, "mem 4"), "004: 00006  result\n");
+        assert_eq!(run(&mut debugger, "mem 3 2"), "003: 00000\n004: 00006  result\n");
+    }
+
+    #[test]
+    fn test_reverse() {
+        let mut debugger: Debugger = debugger();
+
+        run(&mut debugger, "s 3");
+
+        assert_eq!(debugger.simulator().memory()[4], 6);
+        assert_eq!(run(&mut debugger, "rs"), "008: STA 4  (test.txt:7:1)\n");
+        assert_eq!(debugger.simulator().memory()[4], 0);
+        assert_eq!(run(&mut debugger, "rms"), "007: ADD 0  (test.txt:6:1) [execute ADD microstep 2]\n");
+        assert_eq!(run(&mut debugger, "rs 5"), "Reached the start of the history\n006: ADD 0  (test.txt:5:1)\n");
+    }
+
+    #[test]
+    fn test_breakpoints() {
+        let mut debugger: Debugger = debugger();
+
+        assert_eq!(run(&mut debugger, "break :7"), "Breakpoint at 008\n");
+        assert_eq!(run(&mut debugger, "c"), "Breakpoint at 008\n008: STA 4  (test.txt:7:1)\n");
+        assert_eq!(run(&mut debugger, "d 8"), "Deleted the breakpoints and watchpoints at 008\n");
+        assert_eq!(run(&mut debugger, "c"), "Program halted at 009\n");
+        assert_eq!(run(&mut debugger, "break :9"), "error: there is no instruction at line 9\n");
+        assert_eq!(run(&mut debugger, "break XYZ"), "error: 'XYZ' is not a valid address\n");
+        assert_eq!(run(&mut debugger, "break STORE"), "Breakpoint at 008\n");
+    }
+
+    #[test]
+    fn test_watchpoints() {
+        let mut debugger: Debugger = debugger();
+
+        assert_eq!(run(&mut debugger, "watch 1"), "error: '1' is not declared in the variables section\n");
+        assert_eq!(run(&mut debugger, "watch result"), "Watchpoint at 004 = 0000\n");
+        assert_eq!(run(&mut debugger, "c"), "Watchpoint 004: 0000 -> 0006\n009: HALT\n");
+        assert_eq!(debugger.watchpoints(), &vec![4]);
+    }
+
+    #[test]
+    fn test_repl() {
+        let mut debugger: Debugger = debugger();
+        let mut output: Vec<u8> = Vec::new();
+
+        debugger.repl("foo\nq\ns\n".as_bytes(), &mut output).unwrap();
+
+        assert_eq!(
+            String::from_utf8(output).unwrap(),
+            "006: ADD 0  (test.txt:5:1)\n(debug) error: unknown command 'foo', write 'help' to see the commands\n(debug) "
+        );
+    }
+}
\ No newline at end of file
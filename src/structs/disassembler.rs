@@ -0,0 +1,308 @@
+use std::collections::BTreeSet;
+
+use crate::models::{
+    repertoire::Repertoire,
+    instruction::Instruction,
+    microprogram::ControlWord,
+    image::MemoryImage,
+    operand::{Operand, OperandKind},
+    machine::Machine,
+    source::SourceFile,
+    span::Span,
+    args::ImageFormat
+};
+use crate::errors::error::SicompilerError;
+use super::simulator::PC_FROM_MDR;
+
+/// The `Disassembler` struct rebuilds the source of a program from its memory image, the inverse
+/// of the `Assembler`.
+pub struct Disassembler;
+
+impl Disassembler {
+    /// Reads a memory image.
+    ///
+    /// ## Arguments
+    ///
+    /// - `path` - The path the image was read from, used to report errors.
+    /// - `content` - The content of the image.
+    /// - `format` - The format the image is written in.
+    /// - `machine` - The machine the image was assembled for, which gives the size of the memory and of its words.
+    ///
+    /// ## Returns
+    ///
+    /// - `Result<MemoryImage, Error>` - The image, with every word read as used, or an `Error` if it is malformed.
+    ///
+    pub fn read_image(path: &str, content: &[u8], format: ImageFormat, machine: &Machine) -> Result<MemoryImage, SicompilerError> {
+        let words: Vec<u64> = match format {
+            ImageFormat::Bin => {
+                let word_bytes: usize = machine.word_bits().div_ceil(8) as usize;
+
+                if !content.len().is_multiple_of(word_bytes) {
+                    return Err(SicompilerError::MalformedImage {
+                        detail: format!("its size is not a multiple of {} bytes", word_bytes),
+                        span: Span::file_only(path)
+                    });
+                }
+
+                content.chunks(word_bytes)
+                    .map(|bytes: &[u8]| bytes.iter().fold(0, |word: u64, byte: &u8| word << 8 | *byte as u64))
+                    .collect()
+            },
+            ImageFormat::Logisim | ImageFormat::Digital => {
+                let text: String = String::from_utf8_lossy(content).to_string();
+                Disassembler::read_hex(&SourceFile::new(path, &text), format == ImageFormat::Logisim, machine)?
+            }
+        };
+
+        if words.len() > machine.memory_size() {
+            return Err(SicompilerError::MalformedImage {
+                detail: format!("it has {} words but the memory only has {}", words.len(), machine.memory_size()),
+                span: Span::file_only(path)
+            });
+        }
+
+        let mut image: MemoryImage = MemoryImage::new(machine.memory_size(), machine.word_bits());
+
+        for (address, word) in words.iter().enumerate() {
+            image.set(address as u64, *word);
+        }
+
+        Ok(image)
+    }
+
+    /// Reads the words of a text image, separated by whitespace and with `#` comments.
+    ///
+    /// ## Arguments
+    ///
+    /// - `source` - The text of the image.
+    /// - `logisim` - Whether the image must start with the Logisim `v2.0 raw` header.
+    /// - `machine` - The machine, which gives the size of the memory and of its words.
+    ///
+    /// ## Returns
+    ///
+    /// The words of the image or an Error.
+    ///
+    fn read_hex(source: &SourceFile, logisim: bool, machine: &Machine) -> Result<Vec<u64>, SicompilerError> {
+        let content: &str = source.content();
+        let mut words: Vec<u64> = Vec::new();
+        let mut header: bool = !logisim;
+
+        for line in content.lines() {
+            let code: &str = line.split('#').next().unwrap_or_default();
+
+            for token in code.split_whitespace() {
+                let offset: usize = token.as_ptr() as usize - content.as_ptr() as usize;
+                let malformed = |detail: String| SicompilerError::MalformedImage { detail, span: source.span_at(offset, token.len()) };
+
+                if !header {
+                    if code.trim() != "v2.0 raw" {
+                        return Err(malformed("a Logisim image starts with 'v2.0 raw'".to_string()));
+                    }
+
+                    header = true;
+                    break;
+                }
+
+                let (count, word): (&str, &str) = token.split_once('*').unwrap_or(("1", token));
+                let count: usize = count.parse().map_err(|_| malformed(format!("'{}' is not a number of words", count)))?;
+                let word: u64 = u64::from_str_radix(word, 16).ok()
+                    .filter(|word: &u64| *word < 1 << machine.word_bits())
+                    .ok_or_else(|| malformed(format!("'{}' is not a word in hex base", word)))?;
+
+                if words.len() + count > machine.memory_size() {
+                    return Err(malformed(format!("it has more words than the memory, {}", machine.memory_size())));
+                }
+
+                words.extend(std::iter::repeat_n(word, count));
+            }
+        }
+
+        Ok(words)
+    }
+
+    /// Checks whether an instruction jumps, whether its microcode loads PC from its operand.
+    fn is_jump(instruction: &Instruction) -> bool {
+        instruction.microcode().iter().any(|word: &ControlWord| word.value() & PC_FROM_MDR != 0)
+    }
+
+    /// Splits the operand field of an encoded instruction into its operands, as its signature declares them.
+    fn decode(instruction: &Instruction, field: u64) -> Vec<(Operand, u64)> {
+        let mut shift: u32 = instruction.operands().iter().map(|operand: &Operand| operand.width()).sum();
+
+        instruction.operands().iter()
+            .map(|operand: &Operand| {
+                shift -= operand.width();
+                (*operand, field >> shift & operand.max())
+            })
+            .collect()
+    }
+
+    /// Returns the values of the address operands of an encoded instruction.
+    fn addresses(instruction: &Instruction, field: u64) -> Vec<u64> {
+        Disassembler::decode(instruction, field).into_iter()
+            .filter(|(operand, _): &(Operand, u64)| operand.kind() == OperandKind::Address)
+            .map(|(_, value): (Operand, u64)| value)
+            .collect()
+    }
+
+    /// Rebuilds the source of a program from its memory image.
+    ///
+    /// The instructions are decoded from `init` until the first word whose opcode is not in the
+    /// repertoire or the end of the image. The words outside the instructions are declared as
+    /// variables if they are not `0` or an instruction uses them, and a label `L<ADDR>` is
+    /// written before every instruction a jump goes to.
+    ///
+    /// ## Arguments
+    ///
+    /// - `image` - The memory image.
+    /// - `init` - The address of the first instruction.
+    /// - `repertoire` - The repertoire the image was assembled with, which gives the opcodes.
+    ///
+    /// ## Returns
+    ///
+    /// The source of the program, in the three sections of a SiCoMe program.
+    ///
+    pub fn disassemble(image: &MemoryImage, init: u64, repertoire: &Repertoire) -> String {
+        let size: u64 = image.used_len() as u64;
+        let mut code: Vec<(u64, &Instruction, u64)> = Vec::new();
+
+        for address in init..size {
+            let word: u64 = image.get(address);
+
+            match repertoire.instructions().get(repertoire.machine().opcode(word) as usize) {
+                Some(instruction) => code.push((address, instruction, repertoire.machine().operand(word))),
+                None => break
+            }
+        }
+
+        let end: u64 = init + code.len() as u64;
+        let is_code = |address: &u64| (init..end).contains(address);
+
+        //* Addresses a jump goes to get a label, and the ones other instructions use are variables
+        let targets: BTreeSet<u64> = code.iter()
+            .filter(|(_, instruction, _): &&(u64, &Instruction, u64)| Disassembler::is_jump(instruction))
+            .flat_map(|(_, instruction, field): &(u64, &Instruction, u64)| Disassembler::addresses(instruction, *field))
+            .filter(|operand: &u64| is_code(operand))
+            .collect();
+
+        let operands: BTreeSet<u64> = code.iter()
+            .filter(|(_, instruction, _): &&(u64, &Instruction, u64)| !Disassembler::is_jump(instruction))
+            .flat_map(|(_, instruction, field): &(u64, &Instruction, u64)| Disassembler::addresses(instruction, *field))
+            .collect();
+
+        let mut source: String = String::new();
+
+        for address in (0..size).filter(|address: &u64| !is_code(address)) {
+            if image.get(address) != 0 || operands.contains(&address) {
+                source.push_str(&format!("{:X} {:04X}\n", address, image.get(address)));
+            }
+        }
+
+        source.push_str(&format!("@\n{:X}\n@\n", init));
+
+        for (address, instruction, field) in code {
+            if targets.contains(&address) {
+                source.push_str(&format!("L{:03X}:\n", address));
+            }
+
+            source.push_str(instruction.mnemonic());
+
+            for (operand, value) in Disassembler::decode(instruction, field) {
+                match operand.kind() {
+                    OperandKind::Address if targets.contains(&value) && Disassembler::is_jump(instruction) => source.push_str(&format!(" L{:03X}", value)),
+                    OperandKind::Address => source.push_str(&format!(" {:04X}", value)),
+                    OperandKind::Immediate => source.push_str(&format!(" {:X}", value)),
+                    OperandKind::Register => source.push_str(&format!(" R{}", value))
+                }
+            }
+
+            source.push('\n');
+        }
+
+        source
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::structs::{assembler::{ADDRESS_BITS, WORD_BITS}, fixtures::{fetch, microcoded}};
+
+    use super::*;
+
+    fn repertoire() -> Repertoire {
+        Repertoire::new(fetch(), vec![
+            microcoded("HALT", false, &[0]),
+            microcoded("ADD", true, &[0x8000100, 0x1100, 0x28200]),
+            microcoded("SFZ", false, &[0x200600]),
+            microcoded("JMP", true, &[0x400200])
+        ])
+    }
+
+    #[test]
+    fn test_read_image() {
+        let image: MemoryImage = Disassembler::read_image("out.bin", &[0, 0, 3, 0, 0x10, 0x01], ImageFormat::Bin, &Machine::default()).unwrap();
+
+        assert_eq!(image.used_len(), 2);
+        assert_eq!(image.get(1), 0x1001);
+
+        let image: MemoryImage = Disassembler::read_image("out.hex", b"v2.0 raw\n3 3*0 1001 # ADD 1\n", ImageFormat::Logisim, &Machine::default()).unwrap();
+
+        assert_eq!(image.used_len(), 5);
+        assert_eq!(image.get(4), 0x1001);
+
+        let image: MemoryImage = Disassembler::read_image("out.hex", b"3\n0\n1001\n", ImageFormat::Digital, &Machine::default()).unwrap();
+
+        assert_eq!(image.used_len(), 3);
+        assert_eq!(image.get(2), 0x1001);
+
+        let result: Result<MemoryImage, SicompilerError> = Disassembler::read_image("out.bin", &[0, 0, 3, 0], ImageFormat::Bin, &Machine::default());
+        assert_eq!(result.unwrap_err().to_string(), "out.bin: error[SC0025]: Invalid memory image, its size is not a multiple of 3 bytes");
+
+        let result: Result<MemoryImage, SicompilerError> = Disassembler::read_image("out.hex", b"3\n0\n1001\n", ImageFormat::Logisim, &Machine::default());
+        assert_eq!(result.unwrap_err().to_string(), "out.hex:1:1: error[SC0025]: Invalid memory image, a Logisim image starts with 'v2.0 raw'");
+
+        let result: Result<MemoryImage, SicompilerError> = Disassembler::read_image("out.hex", b"3\nG\n", ImageFormat::Digital, &Machine::default());
+        assert_eq!(result.unwrap_err().to_string(), "out.hex:2:1: error[SC0025]: Invalid memory image, 'G' is not a word in hex base");
+    }
+
+    #[test]
+    fn test_disassemble() {
+        let mut image: MemoryImage = MemoryImage::new(1 << ADDRESS_BITS, WORD_BITS);
+        let words: [u64; 10] = [0x3, 0x0, 0x0, 0x0, 0x1000, 0x2000, 0x3008, 0x3004, 0x0, 0x1F000];
+
+        for (address, word) in words.iter().enumerate() {
+            image.set(address as u64, *word);
+        }
+
+        let expected: &str = "\
+0 0003
+9 1F000
+@
+4
+@
+L004:
+ADD 0000
+SFZ
+JMP L008
+JMP L004
+L008:
+HALT
+";
+
+        assert_eq!(Disassembler::disassemble(&image, 4, &repertoire()), expected);
+    }
+
+    #[test]
+    fn test_disassemble_operands() {
+        let mut ldi: Instruction = Instruction::new("LDI", vec![]);
+        ldi.set_operands(vec![Operand::new(OperandKind::Register, 4), Operand::new(OperandKind::Immediate, 8)]);
+
+        let repertoire: Repertoire = Repertoire::new(fetch(), vec![Instruction::new("HALT", vec![]), ldi]);
+
+        let mut image: MemoryImage = MemoryImage::new(1 << ADDRESS_BITS, WORD_BITS);
+        image.set(0, 1 << ADDRESS_BITS | 0x12A);
+        image.set(1, 0);
+
+        assert_eq!(Disassembler::disassemble(&image, 0, &repertoire), "@\n0\n@\nLDI R1 2A\nHALT\n");
+    }
+}
\ No newline at end of file
@@ -0,0 +1,142 @@
+use crate::models::{
+    program::Program,
+    repertoire::Repertoire,
+    image::MemoryImage,
+    symbol::SymbolTable,
+    span::Span,
+    args::OutputFormat
+};
+use crate::errors::error::SicompilerError;
+use super::{assembler::Assembler, literal::Literal, expression::Expression};
+
+/// The `Emitter` struct encodes a validated program in the output formats, in memory, so it can be
+/// written to a file or used without touching the filesystem.
+pub struct Emitter;
+
+impl Emitter {
+    /// Writes a number or an expression in hex, leaving as they are the hex numbers and the registers.
+    fn value(value: &str, symbols: &SymbolTable, address: u64) -> String {
+        if Literal::is_valid(value) {
+            return Literal::normalize(value);
+        }
+
+        Expression::evaluate(value, symbols, address, &Span::default()).map_or(value.to_string(), |value: u64| format!("{:X}", value))
+    }
+
+    /// Writes a program as text, with its labels, variable names and expressions resolved to hex
+    /// values and the numbers written in other bases rewritten in hex.
+    fn text(program: &Program) -> String {
+        let mut text: String = String::new();
+        let symbols: SymbolTable = SymbolTable::from_program(program);
+
+        for variable in program.variables() {
+            let dir: u64 = Literal::parse(variable.dir()).unwrap_or_default();
+            text.push_str(&format!("{} {}\n", Literal::normalize(variable.dir()), Emitter::value(variable.value(), &symbols, dir)));
+        }
+
+        text.push_str(&format!("@\n{}\n@\n", Literal::normalize(program.init().dir())));
+
+        let init: u64 = Literal::parse(program.init().dir()).unwrap_or_default();
+
+        for (address, instruction) in (init..).zip(program.instructions()) {
+            let params: Vec<String> = instruction.params().iter()
+                .map(|param: &String| Emitter::value(param, &symbols, address))
+                .collect();
+
+            text.push_str(&format!("{} {}\n", instruction.mnemonic(), params.join(" ")));
+        }
+
+        text
+    }
+
+    /// Encodes a program in an output format.
+    ///
+    /// ## Arguments
+    ///
+    /// - `program` - The validated program.
+    /// - `repertoire` - The repertoire the program was validated against, which gives the opcodes.
+    /// - `format` - The output format.
+    ///
+    /// ## Returns
+    ///
+    /// - `Result<Vec<u8>, Error>` - The encoded program, or an `Error` if it does not fit in memory.
+    ///
+    pub fn emit(program: &Program, repertoire: &Repertoire, format: OutputFormat) -> Result<Vec<u8>, SicompilerError> {
+        if format == OutputFormat::Text {
+            return Ok(Emitter::text(program).into_bytes());
+        }
+
+        let image: MemoryImage = Assembler::assemble(program, repertoire)?;
+
+        Ok(match format {
+            OutputFormat::Bin => image.to_bin(),
+            OutputFormat::Logisim => image.to_logisim().into_bytes(),
+            OutputFormat::Digital => image.to_digital().into_bytes(),
+            OutputFormat::Ihex => image.to_ihex().into_bytes(),
+            OutputFormat::Srec => {
+                let start: u64 = Assembler::parse_address(program.init().dir(), repertoire.machine(), program.init().span())?;
+                image.to_srec(start).into_bytes()
+            },
+            OutputFormat::Text => unreachable!("the text format does not assemble the program")
+        })
+    }
+
+    /// Encodes the control ROM image of a repertoire, for the formats that write one.
+    ///
+    /// ## Arguments
+    ///
+    /// - `repertoire` - The repertoire with the microcode.
+    /// - `format` - The output format.
+    /// - `control_width` - The number of control signals of each control word.
+    ///
+    /// ## Returns
+    ///
+    /// The encoded control ROM for the Logisim and Digital formats, `None` for the rest.
+    ///
+    pub fn emit_microcode(repertoire: &Repertoire, format: OutputFormat, control_width: u32) -> Option<Vec<u8>> {
+        let microcode: MemoryImage = Assembler::assemble_microcode(repertoire, control_width);
+
+        match format {
+            OutputFormat::Logisim => Some(microcode.to_logisim().into_bytes()),
+            OutputFormat::Digital => Some(microcode.to_digital().into_bytes()),
+            _ => None
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::models::{
+        init::Init,
+        variable::Variable,
+        instruction::Instruction,
+        microprogram::Microprogram
+    };
+    use crate::structs::fixtures::entry;
+
+    use super::*;
+
+    #[test]
+    fn test_emit() {
+        let program: Program = Program::new(vec![Variable::new("0", "3")], Init::new("2"), vec![Instruction::new("ADD", vec!["0"])]);
+        let repertoire: Repertoire = Repertoire::new(Microprogram::default(), vec![Instruction::new("HALT", vec![]), entry("ADD")]);
+
+        assert_eq!(Emitter::emit(&program, &repertoire, OutputFormat::Text).unwrap(), b"0 3\n@\n2\n@\nADD 0\n");
+
+        let literals: Program = Program::new(vec![Variable::new("0b1", "d'25")], Init::new("0x2"), vec![Instruction::new("ADD", vec!["'A'"])]);
+
+        assert_eq!(Emitter::emit(&literals, &repertoire, OutputFormat::Text).unwrap(), b"1 19\n@\n2\n@\nADD 41\n");
+
+        let mut table: Variable = Variable::new("4", "$+1");
+        table.set_symbol("table", Span::default());
+        let expressions: Program = Program::new(vec![table], Init::new("6"), vec![Instruction::new("ADD", vec!["table + 2"]), Instruction::new("ADD", vec!["$-1"])]);
+
+        assert_eq!(Emitter::emit(&expressions, &repertoire, OutputFormat::Text).unwrap(), b"4 5\n@\n6\n@\nADD 6\nADD 6\n");
+        assert_eq!(Emitter::emit(&expressions, &repertoire, OutputFormat::Digital).unwrap(), b"0\n0\n0\n0\n5\n0\n1006\n1006\n");
+        assert_eq!(Emitter::emit(&program, &repertoire, OutputFormat::Bin).unwrap(), vec![0, 0, 3, 0, 0, 0, 0, 0x10, 0]);
+        assert_eq!(Emitter::emit(&program, &repertoire, OutputFormat::Digital).unwrap(), b"3\n0\n1000\n");
+
+        assert!(Emitter::emit_microcode(&repertoire, OutputFormat::Bin, 28).is_none());
+        assert_eq!(Emitter::emit_microcode(&repertoire, OutputFormat::Logisim, 28).unwrap(), b"v2.0 raw\n");
+    }
+}
\ No newline at end of file
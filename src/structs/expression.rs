@@ -0,0 +1,248 @@
+use crate::models::{span::Span, symbol::{Symbol, SymbolTable}};
+use crate::errors::error::SicompilerError;
+use super::literal::Literal;
+
+/// The characters an expression is made of besides its values.
+const OPERATOR_CHARS: &str = "+-*/&|<>()$";
+
+/// The binary operators, from the lowest to the highest precedence level.
+const LEVELS: [&[&str]; 5] = [&["|"], &["&"], &["<<", ">>"], &["+", "-"], &["*", "/"]];
+
+/// A token of an expression, with its values already resolved.
+#[derive(Debug, PartialEq, Clone, Copy)]
+enum Token {
+    Value(u64),
+    Operator(&'static str),
+    Open,
+    Close,
+}
+
+/// Why an expression could not be evaluated, before it is reported with its location.
+enum Fault {
+    Invalid,
+    Overflow,
+    DivisionByZero,
+    Undefined { name: String, offset: usize },
+}
+
+/// The `Expression` struct evaluates the constant expressions of the operands and the data values at assembly time.
+///
+/// An expression combines numbers, labels, variable names and `$`, the address of the instruction or
+/// the variable it is written in, with `+ - * / & | << >>` and parentheses. The operators have the
+/// precedence they have in C, and every intermediate value must be a natural number that fits in 64 bits.
+pub struct Expression;
+
+impl Expression {
+    /// Checks whether an operand is an expression instead of a single number or name.
+    pub fn is_expression(text: &str) -> bool {
+        !Literal::is_valid(text) && text.chars().any(|c: char| OPERATOR_CHARS.contains(c))
+    }
+
+    /// Splits an expression in tokens, resolving its numbers, names and `$`.
+    ///
+    /// ## Arguments
+    ///
+    /// - `text` - The expression.
+    /// - `symbols` - The labels and variables the expression can name.
+    /// - `address` - The value of `$`.
+    ///
+    /// ## Returns
+    ///
+    /// The tokens, or the `Fault` of the first part of the expression that is not a number, a defined name or an operator.
+    ///
+    fn tokenize(text: &str, symbols: &SymbolTable, address: u64) -> Result<Vec<Token>, Fault> {
+        let mut tokens: Vec<Token> = Vec::new();
+        let mut index: usize = 0;
+
+        while index < text.len() {
+            let rest: &str = &text[index..];
+            let Some(c) = rest.chars().next() else { break };
+
+            if c.is_whitespace() {
+                index += c.len_utf8();
+                continue;
+            }
+
+            let operator: Option<&&str> = LEVELS.iter().flat_map(|level: &&[&str]| level.iter()).find(|operator: &&&str| rest.starts_with(**operator));
+
+            let (token, len): (Token, usize) = match (c, operator) {
+                ('(', _) => (Token::Open, 1),
+                (')', _) => (Token::Close, 1),
+                ('$', _) => (Token::Value(address), 1),
+                (_, Some(operator)) => (Token::Operator(operator), operator.len()),
+                _ => {
+                    //* A character literal can hold an operator, so it is read whole before looking for one
+                    let len: usize = match Literal::parse(rest.get(..3).unwrap_or_default()) {
+                        Some(_) if c == '\'' => 3,
+                        _ => rest.find(|c: char| c.is_whitespace() || OPERATOR_CHARS.contains(c)).unwrap_or(rest.len())
+                    };
+
+                    let word: &str = &rest[..len];
+
+                    let value: Option<u64> = symbols.get(word).map(|symbol: &Symbol| symbol.value()).or_else(|| Literal::parse(word));
+
+                    match value {
+                        Some(value) => (Token::Value(value), len),
+                        None if SymbolTable::is_valid_name(word) => return Err(Fault::Undefined { name: word.to_string(), offset: index }),
+                        None if Literal::is_valid(word) => return Err(Fault::Overflow),
+                        None => return Err(Fault::Invalid)
+                    }
+                }
+            };
+
+            tokens.push(token);
+            index += len;
+        }
+
+        Ok(tokens)
+    }
+
+    /// Applies a binary operator, checking the result is a natural number that fits in 64 bits.
+    fn apply(operator: &str, left: u64, right: u64) -> Result<u64, Fault> {
+        let result: Option<u64> = match operator {
+            "+" => left.checked_add(right),
+            "-" => left.checked_sub(right),
+            "*" => left.checked_mul(right),
+            "/" if right == 0 => return Err(Fault::DivisionByZero),
+            "/" => Some(left / right),
+            "&" => Some(left & right),
+            "|" => Some(left | right),
+            "<<" => u32::try_from(right).ok()
+                .and_then(|shift: u32| left.checked_shl(shift))
+                .filter(|result: &u64| result >> right == left),
+            ">>" => Some(u32::try_from(right).ok().and_then(|shift: u32| left.checked_shr(shift)).unwrap_or(0)),
+            _ => return Err(Fault::Invalid)
+        };
+
+        result.ok_or(Fault::Overflow)
+    }
+
+    /// Evaluates the tokens of an expression from `position`, with the operators of `level` or higher.
+    fn binary(tokens: &[Token], position: &mut usize, level: usize) -> Result<u64, Fault> {
+        if level == LEVELS.len() {
+            return Expression::primary(tokens, position);
+        }
+
+        let mut value: u64 = Expression::binary(tokens, position, level + 1)?;
+
+        while let Some(Token::Operator(operator)) = tokens.get(*position) {
+            if !LEVELS[level].contains(operator) { break }
+
+            *position += 1;
+
+            let right: u64 = Expression::binary(tokens, position, level + 1)?;
+            value = Expression::apply(operator, value, right)?;
+        }
+
+        Ok(value)
+    }
+
+    /// Evaluates a value or an expression between parentheses.
+    fn primary(tokens: &[Token], position: &mut usize) -> Result<u64, Fault> {
+        let token: Option<Token> = tokens.get(*position).copied();
+        *position += 1;
+
+        match token {
+            Some(Token::Value(value)) => Ok(value),
+            Some(Token::Open) => {
+                let value: u64 = Expression::binary(tokens, position, 0)?;
+
+                match tokens.get(*position) {
+                    Some(Token::Close) => {
+                        *position += 1;
+                        Ok(value)
+                    },
+                    _ => Err(Fault::Invalid)
+                }
+            },
+            _ => Err(Fault::Invalid)
+        }
+    }
+
+    /// Evaluates an expression, which can also be a single number or name.
+    ///
+    /// ## Arguments
+    ///
+    /// - `text` - The expression, as written in the program.
+    /// - `symbols` - The labels and variables the expression can name.
+    /// - `address` - The address of the instruction or the variable the expression is written in, the value of `$`.
+    /// - `span` - The location of the expression, used to report it.
+    ///
+    /// ## Returns
+    ///
+    /// The value of the expression, or an `Error` if it is malformed, names an undefined symbol,
+    /// overflows or divides by zero.
+    ///
+    pub fn evaluate(text: &str, symbols: &SymbolTable, address: u64, span: &Span) -> Result<u64, SicompilerError> {
+        let result: Result<u64, Fault> = Expression::tokenize(text, symbols, address).and_then(|tokens: Vec<Token>| {
+            let mut position: usize = 0;
+            let value: u64 = Expression::binary(&tokens, &mut position, 0)?;
+
+            if position < tokens.len() { Err(Fault::Invalid) } else { Ok(value) }
+        });
+
+        let expression: String = text.to_string();
+
+        result.map_err(|fault: Fault| match fault {
+            Fault::Invalid => SicompilerError::InvalidExpression { expression, span: span.clone() },
+            Fault::Overflow => SicompilerError::ExpressionOverflow { expression, span: span.clone() },
+            Fault::DivisionByZero => SicompilerError::DivisionByZero { expression, span: span.clone() },
+            //* The undefined name is pointed to inside the expression, when its location is known
+            Fault::Undefined { name, offset } => {
+                let span: Span = match span.line() {
+                    0 => span.clone(),
+                    line => Span::new(span.file(), line, span.column() + offset, name.len())
+                };
+
+                SicompilerError::UndefinedSymbol { name, span }
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::models::symbol::SymbolKind;
+
+    use super::*;
+
+    fn symbols() -> SymbolTable {
+        let mut symbols: SymbolTable = SymbolTable::new();
+        let _ = symbols.insert(Symbol::new("table", SymbolKind::Variable, 0x10, Span::default()));
+        let _ = symbols.insert(Symbol::new("LOOP", SymbolKind::Label, 0x20, Span::default()));
+        symbols
+    }
+
+    #[test]
+    fn test_evaluate() {
+        let symbols: SymbolTable = symbols();
+        let evaluate = |text: &str| Expression::evaluate(text, &symbols, 0x30, &Span::new("test.txt", 1, 5, text.len()));
+
+        assert_eq!(evaluate("table+2").unwrap(), 0x12);
+        assert_eq!(evaluate("$-3").unwrap(), 0x2D);
+        assert_eq!(evaluate("1+2*3").unwrap(), 7);
+        assert_eq!(evaluate("(1+2)*3").unwrap(), 9);
+        assert_eq!(evaluate("1 << 4 | 1").unwrap(), 0x11);
+        assert_eq!(evaluate("LOOP - table >> 1").unwrap(), 8);
+        assert_eq!(evaluate("FF & 0b1010").unwrap(), 0xA);
+        assert_eq!(evaluate("d'10/3").unwrap(), 3);
+        assert_eq!(evaluate("'+'+1").unwrap(), 0x2C);
+        assert_eq!(evaluate("table").unwrap(), 0x10);
+        assert_eq!(evaluate("1F").unwrap(), 0x1F);
+    }
+
+    #[test]
+    fn test_evaluate_errors() {
+        let symbols: SymbolTable = symbols();
+        let evaluate = |text: &str| Expression::evaluate(text, &symbols, 0x30, &Span::new("test.txt", 1, 5, text.len())).unwrap_err().to_string();
+
+        assert_eq!(evaluate("table+missing"), "test.txt:1:11: error[SC0023]: The symbol 'missing' is not defined");
+        assert_eq!(evaluate("table+"), "test.txt:1:5: error[SC0039]: Invalid expression 'table+'");
+        assert_eq!(evaluate("(1+2"), "test.txt:1:5: error[SC0039]: Invalid expression '(1+2'");
+        assert_eq!(evaluate("1 2"), "test.txt:1:5: error[SC0039]: Invalid expression '1 2'");
+        assert_eq!(evaluate("1ZZZ+1"), "test.txt:1:5: error[SC0039]: Invalid expression '1ZZZ+1'");
+        assert_eq!(evaluate("$-31"), "test.txt:1:5: error[SC0040]: The expression '$-31' overflows, its value must be between 0 and 'FFFFFFFFFFFFFFFF'");
+        assert_eq!(evaluate("1<<d'64"), "test.txt:1:5: error[SC0040]: The expression '1<<d'64' overflows, its value must be between 0 and 'FFFFFFFFFFFFFFFF'");
+        assert_eq!(evaluate("table/(1-1)"), "test.txt:1:5: error[SC0041]: The expression 'table/(1-1)' divides by zero");
+    }
+}
\ No newline at end of file
@@ -0,0 +1,238 @@
+use std::fs;
+use std::io::{self, Read, Write};
+use std::path::{Path, PathBuf};
+use std::process;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use crate::models::span::Span;
+use crate::errors::error::SicompilerError;
+
+/// The path that stands for the standard input when reading, and the standard output when writing.
+pub const STDIO_PATH: &str = "-";
+
+/// The name shown in the errors of a program read from the standard input.
+pub const STDIN_NAME: &str = "<stdin>";
+
+/// The number of temporary files staged by this process, which makes the name of each one unique
+/// even when several threads write to the same path.
+static STAGED: AtomicUsize = AtomicUsize::new(0);
+
+/// Returns the name a path is shown with in the errors, `<stdin>` for `-`.
+pub fn display_name(path: &str) -> &str {
+    if path == STDIO_PATH { STDIN_NAME } else { path }
+}
+
+/// Reads the whole content of a file, or of the standard input if the path is `-`.
+///
+/// ## Arguments
+///
+/// - `path` - The path of the file.
+///
+/// ## Returns
+///
+/// - `Result<Vec<u8>, SicompilerError>` - The content or an `Error` if it can not be read.
+///
+pub fn read(path: &str) -> Result<Vec<u8>, SicompilerError> {
+    let result: io::Result<Vec<u8>> = if path == STDIO_PATH {
+        let mut content: Vec<u8> = Vec::new();
+        io::stdin().lock().read_to_end(&mut content).map(|_| content)
+    } else {
+        fs::read(path)
+    };
+
+    result.map_err(|err: io::Error| SicompilerError::Io(io::Error::new(err.kind(), format!("Can't open {}", display_name(path)))))
+}
+
+/// Reads the whole content of a text file, or of the standard input if the path is `-`.
+pub fn read_to_string(path: &str) -> Result<String, SicompilerError> {
+    String::from_utf8(read(path)?).map_err(|_|
+        SicompilerError::Io(io::Error::new(io::ErrorKind::InvalidData, format!("{} is not valid UTF-8", display_name(path))))
+    )
+}
+
+/// Checks whether two paths point to the same existing file.
+fn same_file(first: &str, second: &str) -> bool {
+    match (fs::canonicalize(first), fs::canonicalize(second)) {
+        (Ok(first), Ok(second)) => first == second,
+        _ => false
+    }
+}
+
+/// Checks that an output path does not point to any of the input files, so they are never overwritten.
+///
+/// ## Arguments
+///
+/// - `output` - The path the output will be written to.
+/// - `inputs` - The paths of the files that were read.
+///
+/// ## Returns
+///
+/// - `Result<(), SicompilerError>` - An `Error` if the output is one of the inputs.
+///
+pub fn check_overwrite(output: &str, inputs: &[&str]) -> Result<(), SicompilerError> {
+    if output == STDIO_PATH {
+        return Ok(());
+    }
+
+    match inputs.iter().find(|input: &&&str| same_file(output, input)) {
+        Some(input) => Err(SicompilerError::OverwriteInput { path: output.to_string(), span: Span::file_only(input) }),
+        None => Ok(())
+    }
+}
+
+/// Returns the error of a file that can not be written.
+fn can_not_write(path: &str, err: io::Error) -> SicompilerError {
+    SicompilerError::Io(io::Error::new(err.kind(), format!("Can't write {}", path)))
+}
+
+/// Writes a content to a temporary file in the same directory as a path, to be renamed to it later.
+///
+/// ## Arguments
+///
+/// - `path` - The path of the file.
+/// - `content` - The content to write.
+///
+/// ## Returns
+///
+/// - `Result<PathBuf, SicompilerError>` - The path of the temporary file, or an `Error` if it can not be written.
+///
+fn stage(path: &str, content: &[u8]) -> Result<PathBuf, SicompilerError> {
+    let file_name: String = Path::new(path).file_name().unwrap_or_default().to_string_lossy().to_string();
+    let count: usize = STAGED.fetch_add(1, Ordering::Relaxed);
+    let temporary: PathBuf = Path::new(path).with_file_name(format!(".{}.{}.{}.tmp", file_name, process::id(), count));
+
+    fs::write(&temporary, content).map(|_| temporary.clone()).map_err(|err: io::Error| {
+        let _ = fs::remove_file(&temporary);
+        can_not_write(path, err)
+    })
+}
+
+/// Writes the whole content of a file atomically, or writes it to the standard output if the path is `-`.
+///
+/// The content is written to a temporary file in the same directory, which is then renamed to
+/// the path, so the file is never left half written and a shorter content never leaves bytes of
+/// the previous one.
+///
+/// ## Arguments
+///
+/// - `path` - The path of the file.
+/// - `content` - The content to write.
+///
+/// ## Returns
+///
+/// - `Result<(), SicompilerError>` - An `Error` if the file can not be written.
+///
+pub fn write(path: &str, content: &[u8]) -> Result<(), SicompilerError> {
+    write_all(&[(path.to_string(), content.to_vec())])
+}
+
+/// Writes several files together, or to the standard output the ones whose path is `-`.
+///
+/// Every content is first written to a temporary file next to its path, and the temporary files
+/// are renamed to their paths only once all of them are written, so a file that can not be written
+/// leaves every path untouched. The standard output is written last.
+///
+/// ## Arguments
+///
+/// - `outputs` - The path and the content of each file.
+///
+/// ## Returns
+///
+/// - `Result<(), SicompilerError>` - An `Error` if any of the files can not be written.
+///
+pub fn write_all(outputs: &[(String, Vec<u8>)]) -> Result<(), SicompilerError> {
+    let mut staged: Vec<(PathBuf, &str)> = Vec::new();
+
+    let discard = |staged: &[(PathBuf, &str)]| {
+        for (temporary, _) in staged {
+            let _ = fs::remove_file(temporary);
+        }
+    };
+
+    for (path, content) in outputs.iter().filter(|(path, _): &&(String, Vec<u8>)| path != STDIO_PATH) {
+        match stage(path, content) {
+            Ok(temporary) => staged.push((temporary, path)),
+            Err(err) => {
+                discard(&staged);
+                return Err(err);
+            }
+        }
+    }
+
+    for (index, (temporary, path)) in staged.iter().enumerate() {
+        if let Err(err) = fs::rename(temporary, path) {
+            discard(&staged[index..]);
+            return Err(can_not_write(path, err));
+        }
+    }
+
+    for (_, content) in outputs.iter().filter(|(path, _): &&(String, Vec<u8>)| path == STDIO_PATH) {
+        io::stdout().lock().write_all(content)?;
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_files() {
+        assert_eq!(display_name("-"), "<stdin>");
+        assert_eq!(display_name("program.txt"), "program.txt");
+
+        assert!(write("tests-files/test-files.txt", b"0 0003\n1 0003\n").is_ok());
+        assert!(write("tests-files/test-files.txt", b"0 0003\n").is_ok());
+        assert_eq!(read_to_string("tests-files/test-files.txt").unwrap(), "0 0003\n");
+
+        let result: Result<String, SicompilerError> = read_to_string("tests-files/missing.txt");
+        assert_eq!(result.unwrap_err().to_string(), "error[SC0001]: I/O error: Can't open tests-files/missing.txt");
+
+        let result: Result<(), SicompilerError> = write("tests-files/missing/out.txt", b"");
+        assert_eq!(result.unwrap_err().to_string(), "error[SC0001]: I/O error: Can't write tests-files/missing/out.txt");
+        assert!(fs::read_dir("tests-files").unwrap().all(|entry| !entry.unwrap().file_name().to_string_lossy().starts_with(".test-files.txt.")));
+    }
+
+    #[test]
+    fn test_write_concurrent() {
+        let contents: Vec<Vec<u8>> = (0..8).map(|index: usize| format!("{:04X}\n", index).repeat(1000).into_bytes()).collect();
+
+        std::thread::scope(|scope: &std::thread::Scope<'_, '_>| {
+            for content in &contents {
+                scope.spawn(move || assert!(write("tests-files/test-write-concurrent.txt", content).is_ok()));
+            }
+        });
+
+        let written: Vec<u8> = fs::read("tests-files/test-write-concurrent.txt").unwrap();
+
+        assert!(contents.contains(&written));
+        assert!(fs::read_dir("tests-files").unwrap().all(|entry| !entry.unwrap().file_name().to_string_lossy().starts_with(".test-write-concurrent.txt.")));
+        assert!(fs::remove_file("tests-files/test-write-concurrent.txt").is_ok());
+    }
+
+    #[test]
+    fn test_write_all() {
+        assert!(write("tests-files/test-write-all.txt", b"0 0003\n").is_ok());
+
+        let outputs: Vec<(String, Vec<u8>)> = vec![
+            ("tests-files/test-write-all.txt".to_string(), b"0 0004\n".to_vec()),
+            ("tests-files/missing/out-microcode.txt".to_string(), b"v2.0 raw\n".to_vec())
+        ];
+        let result: Result<(), SicompilerError> = write_all(&outputs);
+
+        assert_eq!(result.unwrap_err().to_string(), "error[SC0001]: I/O error: Can't write tests-files/missing/out-microcode.txt");
+        assert_eq!(read_to_string("tests-files/test-write-all.txt").unwrap(), "0 0003\n");
+        assert!(fs::read_dir("tests-files").unwrap().all(|entry| !entry.unwrap().file_name().to_string_lossy().starts_with(".test-write-all.txt.")));
+    }
+
+    #[test]
+    fn test_check_overwrite() {
+        assert!(check_overwrite("tests-files/./test-input.txt", &["tests-files/test-repertoire.rep", "tests-files/test-input.txt"]).is_err());
+        assert!(check_overwrite("tests-files/out.txt", &["tests-files/test-input.txt"]).is_ok());
+        assert!(check_overwrite("-", &["-"]).is_ok());
+
+        let result: Result<(), SicompilerError> = check_overwrite("tests-files/test-input.txt", &["tests-files/test-input.txt"]);
+        assert_eq!(result.unwrap_err().to_string(), "tests-files/test-input.txt: error[SC0027]: The output path 'tests-files/test-input.txt' is an input file");
+    }
+}
\ No newline at end of file
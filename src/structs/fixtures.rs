@@ -0,0 +1,29 @@
+use crate::models::{
+    instruction::Instruction,
+    operand::{Operand, OperandKind},
+    microprogram::{Microprogram, ControlWord},
+    span::Span
+};
+use super::assembler::ADDRESS_BITS;
+
+/// Builds an instruction of the repertoire that takes one address.
+pub(crate) fn entry(mnemonic: &str) -> Instruction {
+    let mut instruction: Instruction = Instruction::new(mnemonic, vec![]);
+    instruction.set_operands(vec![Operand::new(OperandKind::Address, ADDRESS_BITS)]);
+    instruction
+}
+
+/// The control words of the fetch cycle of the SiCoMe machine.
+pub(crate) const FETCH: [u64; 3] = [0x4000100, 0x0201100, 0x3000300];
+
+/// Builds the microprogram with the fetch cycle of the SiCoMe machine.
+pub(crate) fn fetch() -> Microprogram {
+    Microprogram::new(FETCH.iter().map(|value: &u64| ControlWord::new(*value, Span::default())).collect())
+}
+
+/// Builds an instruction of the repertoire with the specified microcode, which takes one address if `flag` is set.
+pub(crate) fn microcoded(mnemonic: &str, flag: bool, microcode: &[u64]) -> Instruction {
+    let mut instruction: Instruction = if flag { entry(mnemonic) } else { Instruction::new(mnemonic, vec![]) };
+    instruction.set_microcode(microcode.iter().map(|value: &u64| ControlWord::new(*value, Span::default())).collect());
+    instruction
+}
\ No newline at end of file
@@ -0,0 +1,137 @@
+use super::literal::Literal;
+
+/// The `Formatter` struct rewrites the source of a program in a canonical layout, keeping its comments.
+///
+/// Each token is separated by one space, with no indentation, and the hex numbers are written in
+/// uppercase, leaving the numbers with a base marker as they are. One-line comments are moved two spaces after the code, multi-line comments are left
+/// as they are, and consecutive blank lines are collapsed into one.
+pub struct Formatter;
+
+impl Formatter {
+    /// Writes in uppercase the tokens of a line that are hex numbers, leaving names and mnemonics as they are.
+    ///
+    /// ## Arguments
+    ///
+    /// - `tokens` - The tokens of the line.
+    /// - `section` - The section of the program the line is in: variables, init or instructions.
+    ///
+    /// ## Returns
+    ///
+    /// The tokens joined by one space.
+    ///
+    fn format_tokens(tokens: &[&str], section: usize) -> String {
+        let labels: usize = tokens.iter().take_while(|token: &&&str| token.ends_with(':')).count();
+
+        tokens.iter().enumerate()
+            .map(|(index, token): (usize, &&str)| {
+                let is_number: bool = match section {
+                    0 => index < 2,
+                    1 => true,
+                    _ => index > labels
+                };
+
+                if is_number && Literal::is_hex(token) {
+                    token.to_uppercase()
+                } else {
+                    token.to_string()
+                }
+            })
+            .collect::<Vec<String>>()
+            .join(" ")
+    }
+
+    /// Formats the source of a program.
+    ///
+    /// ## Arguments
+    ///
+    /// - `content` - The source of the program.
+    ///
+    /// ## Returns
+    ///
+    /// The formatted source, ending with a line break.
+    ///
+    pub fn format(content: &str) -> String {
+        let mut lines: Vec<String> = Vec::new();
+        let mut in_comment: bool = false;
+        let mut section: usize = 0;
+
+        for line in content.lines() {
+            let (code, comment): (&str, Option<&str>) = match line.find('#') {
+                Some(index) => (&line[..index], Some(line[index..].trim_end())),
+                None => (line, None)
+            };
+
+            let trimmed_code: &str = code.trim();
+
+            //* Multi-line comments are detected as the tokenizer does, and kept as they are
+            if trimmed_code.starts_with("***") || trimmed_code.ends_with("***") || in_comment {
+                in_comment = match (trimmed_code.starts_with("***"), trimmed_code.ends_with("***")) {
+                    (true, _) => true,
+                    (false, true) => false,
+                    _ => in_comment
+                };
+
+                lines.push(line.trim_end().to_string());
+                continue;
+            }
+
+            let tokens: Vec<&str> = trimmed_code.split_whitespace().collect();
+            let code: String = if trimmed_code.contains('@') {
+                section += trimmed_code.matches('@').count();
+                tokens.join(" ")
+            } else {
+                Formatter::format_tokens(&tokens, section)
+            };
+
+            let formatted: String = match (code.is_empty(), comment) {
+                (true, Some(comment)) => comment.to_string(),
+                (false, Some(comment)) => format!("{}  {}", code, comment),
+                _ => code
+            };
+
+            if formatted.is_empty() && lines.last().is_none_or(|last: &String| last.is_empty()) {
+                continue;
+            }
+
+            lines.push(formatted);
+        }
+
+        while lines.last().is_some_and(|last: &String| last.is_empty()) {
+            lines.pop();
+        }
+
+        lines.iter().map(|line: &String| format!("{}\n", line)).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_format() {
+        let content: &str = "\n\n0   0003   count#Counter\n1 ff\n\n\n***  multi-line\n   comment ***\n@\n  a\n@\n\
+LOOP:   ADD   count\n    add 00ff   # lowercase mnemonic\n\tJMP LOOP   \n\n\n";
+
+        let expected: &str = "\
+0 0003 count  #Counter
+1 FF
+
+***  multi-line
+   comment ***
+@
+A
+@
+LOOP: ADD count
+add 00FF  # lowercase mnemonic
+JMP LOOP
+";
+
+        assert_eq!(Formatter::format(content), expected);
+        assert_eq!(Formatter::format(expected), expected);
+
+        let literals: &str = "0 d'25\n1 0b1010 'a'\n@\n0x6\n@\nADD 25d\n";
+
+        assert_eq!(Formatter::format(literals), literals);
+    }
+}
\ No newline at end of file
@@ -0,0 +1,190 @@
+use std::collections::HashMap;
+
+use crate::models::{
+    program::Program,
+    repertoire::Repertoire,
+    source::SourceFile,
+    image::MemoryImage,
+    symbol::{Symbol, SymbolKind, SymbolTable}
+};
+use crate::errors::error::SicompilerError;
+use super::{assembler::Assembler, expression::Expression};
+
+/// The `Listing` struct writes a classic assembler listing of a program: every source line with
+/// the address and the encoded word it ended up at, followed by the symbol table.
+pub struct Listing;
+
+impl Listing {
+    /// Renders the listing of a program.
+    ///
+    /// Each line of the source is written as is, comments included, after the address and the word
+    /// of the variable or instruction it declares. Instructions with a label, a variable or a constant
+    /// name as operand, or with an expression, also show the operand as written and the value it was
+    /// resolved to.
+    ///
+    /// ## Arguments
+    ///
+    /// - `program` - The validated program.
+    /// - `repertoire` - The repertoire the program was validated against, which gives the opcodes.
+    /// - `source` - The file the program was read from.
+    ///
+    /// ## Returns
+    ///
+    /// - `Result<String, Error>` - The listing, or an `Error` if the program does not fit in memory.
+    ///
+    pub fn render(program: &Program, repertoire: &Repertoire, source: &SourceFile) -> Result<String, SicompilerError> {
+        let image: MemoryImage = Assembler::assemble(program, repertoire)?;
+        let init: u64 = Assembler::parse_address(program.init().dir(), repertoire.machine(), program.init().span())?;
+        let symbols: SymbolTable = SymbolTable::from_program(program);
+
+        let address_width: usize = repertoire.machine().address_bits().div_ceil(4) as usize;
+        let word_width: usize = repertoire.machine().word_bits().div_ceil(4) as usize;
+
+        //* The address each line was stored at, with the symbols it uses
+        let mut cells: HashMap<usize, (u64, Vec<String>)> = HashMap::new();
+
+        for variable in program.variables() {
+            let address: u64 = Assembler::parse_address(variable.dir(), repertoire.machine(), variable.dir_span())?;
+            cells.insert(variable.dir_span().line(), (address, vec![]));
+        }
+
+        for (index, instruction) in program.instructions().iter().enumerate() {
+            let address: u64 = init + index as u64;
+            let resolved: Vec<String> = instruction.params().iter()
+                .filter_map(|param: &String| match symbols.get(param) {
+                    Some(symbol) => Some(symbol.value()),
+                    None if Expression::is_expression(param) => Expression::evaluate(param, &symbols, address, instruction.span()).ok(),
+                    None => None
+                }.map(|value: u64| (param, value)))
+                .map(|(param, value): (&String, u64)| format!("{} = {:0width$X}", param, value, width = address_width))
+                .collect();
+
+            cells.insert(instruction.span().line(), (address, resolved));
+        }
+
+        let column_width: usize = address_width.max("ADDR".len());
+        let mut listing: String = format!("{:<column_width$}  {:<word_width$}  {:>4}  SOURCE\n", "ADDR", "WORD", "LINE");
+
+        for line in 1..=source.content().lines().count() {
+            let text: &str = source.line(line).unwrap_or_default();
+            let row: String = match cells.get(&line) {
+                Some((address, resolved)) => {
+                    let notes: String = if resolved.is_empty() { String::new() } else { format!("  ; {}", resolved.join(", ")) };
+
+                    let address_text: String = format!("{:0address_width$X}", address);
+
+                    format!("{:<column_width$}  {:0word_width$X}  {:>4}  {}{}", address_text, image.get(*address), line, text, notes)
+                },
+                None => format!("{:column_width$}  {:word_width$}  {:>4}  {}", "", "", line, text)
+            };
+
+            listing.push_str(row.trim_end());
+            listing.push('\n');
+        }
+
+        let mut table: Vec<&Symbol> = symbols.symbols().iter().collect();
+        table.sort_by(|a: &&Symbol, b: &&Symbol| a.name().cmp(b.name()));
+
+        let name_width: usize = table.iter().map(|symbol: &&Symbol| symbol.name().len()).max().unwrap_or_default().max(6);
+
+        listing.push_str(&format!("\n{:<name_width$}  {:<8}  VALUE\n", "SYMBOL", "KIND"));
+
+        for symbol in table {
+            let kind: &str = match symbol.kind() {
+                SymbolKind::Label => "label",
+                SymbolKind::Variable => "variable",
+                SymbolKind::Constant => "constant"
+            };
+
+            listing.push_str(&format!("{:<name_width$}  {:<8}  {:0address_width$X}\n", symbol.name(), kind, symbol.value()));
+        }
+
+        Ok(listing)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::models::{
+        init::Init,
+        variable::Variable,
+        instruction::Instruction,
+        label::Label,
+        constant::Constant,
+        span::Span,
+        microprogram::Microprogram
+    };
+
+    use crate::structs::fixtures::entry;
+
+    use super::*;
+
+    #[test]
+    fn test_render() {
+        let source: SourceFile = SourceFile::new("program.txt", "0 0003 count  #Counter\n@\n4\n@\nLOOP: ADD count\nJMP LOOP #Again\n");
+
+        let mut variable: Variable = Variable::new("0", "0003");
+        variable.set_spans(Span::new("program.txt", 1, 1, 1), Span::new("program.txt", 1, 3, 4));
+        variable.set_symbol("count", Span::new("program.txt", 1, 8, 5));
+
+        let mut add: Instruction = Instruction::new("ADD", vec!["count"]);
+        add.set_span(Span::new("program.txt", 5, 7, 3));
+
+        let mut jmp: Instruction = Instruction::new("JMP", vec!["LOOP"]);
+        jmp.set_span(Span::new("program.txt", 6, 1, 3));
+
+        let mut program: Program = Program::new(vec![variable], Init::new("4"), vec![add, jmp]);
+        program.set_labels(vec![Label::new("LOOP", 0)]);
+
+        let repertoire: Repertoire = Repertoire::new(Microprogram::default(), vec![
+            entry("ADD"),
+            entry("JMP")
+        ]);
+
+        let listing: String = Listing::render(&program, &repertoire, &source).unwrap();
+        let expected: &str = "\
+ADDR  WORD   LINE  SOURCE
+000   00003     1  0 0003 count  #Counter
+                2  @
+                3  4
+                4  @
+004   00000     5  LOOP: ADD count  ; count = 000
+005   01004     6  JMP LOOP #Again  ; LOOP = 004
+
+SYMBOL  KIND      VALUE
+LOOP    label     004
+count   variable  000
+";
+
+        assert_eq!(listing, expected);
+    }
+
+    #[test]
+    fn test_render_expressions() {
+        let source: SourceFile = SourceFile::new("program.txt", "0 0003 count\n@\n4\n@\n.equ STEP 2\nADD count+1\nJMP $+STEP\n");
+
+        let mut variable: Variable = Variable::new("0", "0003");
+        variable.set_spans(Span::new("program.txt", 1, 1, 1), Span::new("program.txt", 1, 3, 4));
+        variable.set_symbol("count", Span::new("program.txt", 1, 8, 5));
+
+        let mut add: Instruction = Instruction::new("ADD", vec!["count+1"]);
+        add.set_span(Span::new("program.txt", 6, 1, 3));
+
+        let mut jmp: Instruction = Instruction::new("JMP", vec!["$+STEP"]);
+        jmp.set_span(Span::new("program.txt", 7, 1, 3));
+
+        let mut program: Program = Program::new(vec![variable], Init::new("4"), vec![add, jmp]);
+        program.set_constants(vec![Constant::new("STEP", "2", 0)]);
+
+        let repertoire: Repertoire = Repertoire::new(Microprogram::default(), vec![
+            entry("ADD"),
+            entry("JMP")
+        ]);
+
+        let listing: String = Listing::render(&program, &repertoire, &source).unwrap();
+
+        assert!(listing.contains("004   00001     6  ADD count+1  ; count+1 = 001\n"));
+        assert!(listing.contains("005   01007     7  JMP $+STEP  ; $+STEP = 007\n"));
+        assert!(listing.contains("STEP    constant  002\n"));
+    }
+}
\ No newline at end of file
@@ -0,0 +1,140 @@
+/// The `Literal` struct reads the numbers written in a program, which can be in several bases.
+///
+/// A number is hex by default, like `1F`. It can also be written in hex with `0x`, in binary with
+/// `0b`, in octal with `0o`, in decimal with `d'` before it or `d` after it, or as a character
+/// between quotes, like `'A'`. The base markers are lowercase, so `0B10` or `25D` are still hex.
+///
+/// A marker wins over bare hex, so `10d` is decimal `10` and `0b1` binary `1`, even though older
+/// programs, written when every number was hex, meant `10D` and `B1` with them.
+pub struct Literal;
+
+impl Literal {
+    /// Splits a number in its digits and its base, checking every digit belongs to it.
+    ///
+    /// ## Arguments
+    ///
+    /// - `value` - The number as written in the program, which can not be a character.
+    ///
+    /// ## Returns
+    ///
+    /// The digits and the base, or `None` if the number is not well written.
+    ///
+    fn split(value: &str) -> Option<(&str, u32)> {
+        let prefixes: [(&str, u32); 4] = [("0x", 16), ("0b", 2), ("0o", 8), ("d'", 10)];
+
+        let (digits, radix): (&str, u32) = prefixes.iter()
+            .find_map(|(prefix, radix): &(&str, u32)| {
+                value.strip_prefix(prefix).filter(|digits: &&str| !digits.is_empty()).map(|digits: &str| (digits, *radix))
+            })
+            .or_else(|| {
+                value.strip_suffix('d')
+                    .filter(|digits: &&str| !digits.is_empty() && digits.chars().all(|c: char| c.is_ascii_digit()))
+                    .map(|digits: &str| (digits, 10))
+            })
+            .unwrap_or((value, 16));
+
+        if digits.is_empty() || !digits.chars().all(|c: char| c.is_digit(radix)) {
+            return None;
+        }
+
+        Some((digits, radix))
+    }
+
+    /// Returns the character of a character literal, the only one between two quotes.
+    fn character(value: &str) -> Option<char> {
+        let mut chars = value.strip_prefix('\'')?.strip_suffix('\'')?.chars();
+
+        match (chars.next(), chars.next()) {
+            (Some(c), None) => Some(c),
+            _ => None
+        }
+    }
+
+    /// Checks whether a number is well written in one of the accepted bases, even if it is too large to be read.
+    pub fn is_valid(value: &str) -> bool {
+        Literal::character(value).is_some() || Literal::split(value).is_some()
+    }
+
+    /// Checks whether a number is written in hex without any base marker, as it is written in the output.
+    pub fn is_hex(value: &str) -> bool {
+        Literal::split(value).is_some_and(|(digits, radix): (&str, u32)| radix == 16 && digits.len() == value.len())
+    }
+
+    /// Reads a number written in any of the accepted bases.
+    ///
+    /// ## Arguments
+    ///
+    /// - `value` - The number as written in the program.
+    ///
+    /// ## Returns
+    ///
+    /// The value of the number, or `None` if it is not well written or does not fit in 64 bits.
+    ///
+    pub fn parse(value: &str) -> Option<u64> {
+        if let Some(c) = Literal::character(value) {
+            return Some(c as u64);
+        }
+
+        Literal::split(value).and_then(|(digits, radix): (&str, u32)| u64::from_str_radix(digits, radix).ok())
+    }
+
+    /// Rewrites a number in hex base, leaving as they are the numbers already in hex and the tokens that are not numbers.
+    pub fn normalize(value: &str) -> String {
+        match Literal::parse(value) {
+            Some(number) if !Literal::is_hex(value) => format!("{:X}", number),
+            _ => value.to_string()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        assert_eq!(Literal::parse("1F"), Some(0x1F));
+        assert_eq!(Literal::parse("0x1F"), Some(0x1F));
+        assert_eq!(Literal::parse("0b1010"), Some(10));
+        assert_eq!(Literal::parse("0o17"), Some(15));
+        assert_eq!(Literal::parse("d'25"), Some(25));
+        assert_eq!(Literal::parse("25d"), Some(25));
+        assert_eq!(Literal::parse("'A'"), Some(65));
+
+        assert_eq!(Literal::parse("0B10"), Some(0xB10));
+        assert_eq!(Literal::parse("25D"), Some(0x25D));
+        assert_eq!(Literal::parse("1ad"), Some(0x1AD));
+        assert_eq!(Literal::parse("0b"), Some(0xB));
+        assert_eq!(Literal::parse("10d"), Some(10));
+        assert_eq!(Literal::parse("0b1"), Some(1));
+
+        assert_eq!(Literal::parse("0b102"), None);
+        assert_eq!(Literal::parse("0x"), None);
+        assert_eq!(Literal::parse("d'1A"), None);
+        assert_eq!(Literal::parse("'AB'"), None);
+        assert_eq!(Literal::parse("1ZZZ"), None);
+        assert_eq!(Literal::parse("+5"), None);
+        assert_eq!(Literal::parse(""), None);
+    }
+
+    #[test]
+    fn test_is_valid() {
+        assert!(Literal::is_valid("FFFFFFFFFFFFFFFFFFFF"));
+        assert!(Literal::is_valid("'z'"));
+        assert!(!Literal::is_valid("count"));
+        assert!(!Literal::is_valid("R3"));
+    }
+
+    #[test]
+    fn test_normalize() {
+        assert_eq!(Literal::normalize("0003"), "0003");
+        assert_eq!(Literal::normalize("d'25"), "19");
+        assert_eq!(Literal::normalize("0b1010"), "A");
+        assert_eq!(Literal::normalize("'A'"), "41");
+        assert_eq!(Literal::normalize("count"), "count");
+
+        assert!(Literal::is_hex("ff"));
+        assert!(!Literal::is_hex("25d"));
+        assert!(!Literal::is_hex("0x1F"));
+    }
+}
\ No newline at end of file
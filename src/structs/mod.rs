@@ -1,2 +1,16 @@
+pub mod assembler;
+pub mod literal;
+pub mod expression;
 pub mod tokenizer;
-pub mod validator;
\ No newline at end of file
+pub mod validator;
+pub mod repertoire_validator;
+pub mod simulator;
+pub mod debugger;
+pub mod listing;
+pub mod disassembler;
+pub mod formatter;
+pub mod files;
+pub mod emitter;
+
+#[cfg(test)]
+mod fixtures;
\ No newline at end of file
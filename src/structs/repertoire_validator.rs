@@ -0,0 +1,110 @@
+use std::collections::HashMap;
+
+use crate::models::{
+    repertoire::Repertoire,
+    span::Span
+};
+use crate::errors::{error::SicompilerError, diagnostics::Diagnostics};
+
+/// The `RepertoireValidator` struct checks a tokenized repertoire as a whole, once every line
+/// of it has been read, reporting the instructions that can not be told apart or used.
+pub struct RepertoireValidator<'a> {
+    repertoire: &'a Repertoire,
+    path: String
+}
+
+impl<'a> RepertoireValidator<'a> {
+    /// Creates a new `RepertoireValidator` for a repertoire.
+    ///
+    /// ## Arguments
+    ///
+    /// - `repertoire` - The tokenized repertoire.
+    /// - `path` - The name of the repertoire file, used by the errors that do not point to a line.
+    ///
+    /// ## Returns
+    ///
+    /// A new `RepertoireValidator` instance.
+    pub fn new(repertoire: &'a Repertoire, path: &str) -> RepertoireValidator<'a> {
+        RepertoireValidator { repertoire, path: path.to_string() }
+    }
+
+    /// Validates that the repertoire declares at least one instruction.
+    ///
+    /// ## Arguments
+    ///
+    /// - `&self` - Reference to the `RepertoireValidator` instance.
+    /// - `diagnostics` - The collector where the error is reported if the instructions section is empty.
+    ///
+    fn validate_instructions(&self, diagnostics: &mut Diagnostics) {
+        if self.repertoire.is_empty() {
+            diagnostics.push(SicompilerError::EmptyRepertoire { span: Span::file_only(&self.path) });
+        }
+    }
+
+    /// Validates that every mnemonic is declared once, as the program could only use the first one.
+    ///
+    /// ## Arguments
+    ///
+    /// - `&self` - Reference to the `RepertoireValidator` instance.
+    /// - `diagnostics` - The collector where every repeated mnemonic is reported.
+    ///
+    fn validate_mnemonics(&self, diagnostics: &mut Diagnostics) {
+        let mut declared: HashMap<&str, &Span> = HashMap::new();
+
+        for instruction in self.repertoire.instructions() {
+            match declared.get(instruction.mnemonic()) {
+                Some(first) => diagnostics.push(SicompilerError::DuplicateMnemonic {
+                    mnemonic: instruction.mnemonic().to_string(),
+                    first: (*first).clone(),
+                    span: instruction.span().clone()
+                }),
+                None => { declared.insert(instruction.mnemonic(), instruction.span()); }
+            }
+        }
+    }
+
+    /// Validates the repertoire, pushing every problem found to `diagnostics`.
+    ///
+    /// ## Arguments
+    ///
+    /// - `&self` - Reference to the `RepertoireValidator` instance.
+    /// - `diagnostics` - The collector where every problem is reported.
+    ///
+    pub fn validate(&self, diagnostics: &mut Diagnostics) {
+        self.validate_instructions(diagnostics);
+        self.validate_mnemonics(diagnostics);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::models::{instruction::Instruction, microprogram::Microprogram};
+
+    use super::*;
+
+    #[test]
+    fn test_validate_instructions() {
+        let repertoire: Repertoire = Repertoire::new(Microprogram::default(), vec![]);
+        let mut diagnostics: Diagnostics = Diagnostics::new();
+        RepertoireValidator::new(&repertoire, "test.rep").validate(&mut diagnostics);
+
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics.errors()[0].to_string(), "test.rep: error[SC0028]: The repertoire does not declare any instruction");
+    }
+
+    #[test]
+    fn test_validate_mnemonics() {
+        let mut first: Instruction = Instruction::new("ADD", vec![]);
+        first.set_span(Span::new("test.rep", 2, 1, 3));
+
+        let mut second: Instruction = Instruction::new("ADD", vec![]);
+        second.set_span(Span::new("test.rep", 4, 1, 3));
+
+        let repertoire: Repertoire = Repertoire::new(Microprogram::default(), vec![first, Instruction::new("HALT", vec![]), second]);
+        let mut diagnostics: Diagnostics = Diagnostics::new();
+        RepertoireValidator::new(&repertoire, "test.rep").validate(&mut diagnostics);
+
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics.errors()[0].to_string(), "test.rep:4:1: error[SC0031]: The mnemonic 'ADD' is declared more than once");
+    }
+}
\ No newline at end of file
@@ -0,0 +1,661 @@
+use std::collections::VecDeque;
+
+use crate::models::{
+    program::Program,
+    repertoire::Repertoire,
+    instruction::Instruction,
+    microprogram::ControlWord,
+    image::MemoryImage,
+    machine::Machine
+};
+use crate::errors::error::SicompilerError;
+use super::assembler::Assembler;
+
+/// The number of microsteps a program can run before the simulator stops it.
+pub const DEFAULT_MAX_STEPS: usize = 1_000_000;
+
+/// Control signals of the SiCoMe datapath, as laid out by the microcode of the SiCoMe repertoires
+/// (the fetch cycle is `4000100 0201100 3000300`). The sources of MAR and MDR, the destination
+/// of MDR and the ALU operation are fields of several bits.
+const FIN: u64 = 0x200;
+const COND: u64 = 0x400;
+const MDR_SOURCE_SHIFT: u32 = 12;
+const MDR_SOURCE_MASK: u64 = 0x7;
+const ALU_SHIFT: u32 = 15;
+const ALU_MASK: u64 = 0x7;
+const ALU_TO_F: u64 = 0x80000;
+const PC_INC: u64 = 0x200000;
+pub(crate) const PC_FROM_MDR: u64 = 0x400000;
+const MDR_TARGET_SHIFT: u32 = 24;
+const MAR_SOURCE_SHIFT: u32 = 26;
+const FIELD_MASK: u64 = 0x3;
+
+/// The phase of the instruction cycle the simulator is running.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum Phase {
+    /// Running the fetch cycle of the microprogram section.
+    Fetch,
+    /// Running the microcode of the instruction with the specified opcode.
+    Execute(usize),
+}
+
+/// The registers before a microstep, and the memory cell it overwrote, used to undo it.
+#[derive(Debug, Clone)]
+struct Record {
+    acc: u64,
+    f: bool,
+    z: bool,
+    pc: u64,
+    mar: u64,
+    mdr: u64,
+    ir: u64,
+    phase: Phase,
+    microstep: usize,
+    address: u64,
+    halted: bool,
+    steps: usize,
+    write: Option<(usize, u64)>,
+}
+
+/// The `Simulator` struct runs a validated program on the SiCoMe machine, driving the datapath
+/// with the control words of the repertoire, one microstep at a time.
+///
+/// The memory starts with the image built by the `Assembler`.
+#[derive(Debug, Clone)]
+pub struct Simulator {
+    memory: Vec<u64>,
+    acc: u64,
+    f: bool,
+    z: bool,
+    pc: u64,
+    mar: u64,
+    mdr: u64,
+    ir: u64,
+    fetch: Vec<u64>,
+    microcode: Vec<Vec<u64>>,
+    mnemonics: Vec<String>,
+    machine: Machine,
+    phase: Phase,
+    microstep: usize,
+    address: u64,
+    halted: bool,
+    steps: usize,
+    max_steps: usize,
+    history: VecDeque<Record>,
+    history_limit: usize,
+}
+
+impl Simulator {
+    /// Creates a new `Simulator` with the program loaded in memory and PC pointing to its init address.
+    ///
+    /// ## Arguments
+    ///
+    /// - `program` - The validated program to run.
+    /// - `repertoire` - The repertoire the program was validated against, with its microcode.
+    ///
+    /// ## Returns
+    ///
+    /// - `Result<Simulator, Error>` - The simulator, or an `Error` if the program does not fit in memory.
+    ///
+    pub fn new(program: &Program, repertoire: &Repertoire) -> Result<Simulator, SicompilerError> {
+        let image: MemoryImage = Assembler::assemble(program, repertoire)?;
+        let init: u64 = Assembler::parse_address(program.init().dir(), repertoire.machine(), program.init().span())?;
+
+        let words = |words: &Vec<ControlWord>| words.iter().map(|word: &ControlWord| word.value()).collect::<Vec<u64>>();
+
+        Ok(Simulator {
+            memory: image.words().clone(),
+            acc: 0,
+            f: false,
+            z: true,
+            pc: init,
+            mar: 0,
+            mdr: 0,
+            ir: 0,
+            fetch: words(repertoire.microprogram().fetch()),
+            microcode: repertoire.instructions().iter().map(|instruction: &Instruction| words(instruction.microcode())).collect(),
+            mnemonics: repertoire.instructions().iter().map(|instruction: &Instruction| instruction.mnemonic().to_string()).collect(),
+            machine: *repertoire.machine(),
+            phase: Phase::Fetch,
+            microstep: 0,
+            address: init,
+            halted: false,
+            steps: 0,
+            max_steps: DEFAULT_MAX_STEPS,
+            history: VecDeque::new(),
+            history_limit: 0,
+        })
+    }
+
+    pub fn memory(&self) -> &Vec<u64> { &self.memory }
+    pub fn acc(&self) -> u64 { self.acc }
+    pub fn f(&self) -> bool { self.f }
+    pub fn z(&self) -> bool { self.z }
+    pub fn pc(&self) -> u64 { self.pc }
+    pub fn mar(&self) -> u64 { self.mar }
+    pub fn mdr(&self) -> u64 { self.mdr }
+    pub fn ir(&self) -> u64 { self.ir }
+    pub fn phase(&self) -> Phase { self.phase }
+    pub fn microstep(&self) -> usize { self.microstep }
+    pub fn halted(&self) -> bool { self.halted }
+    pub fn steps(&self) -> usize { self.steps }
+
+    /// Returns the address of the instruction being run.
+    pub fn address(&self) -> u64 { self.address }
+
+    /// Returns the mnemonic of the instruction with the specified opcode.
+    pub fn mnemonic(&self, opcode: usize) -> Option<&str> { self.mnemonics.get(opcode).map(|mnemonic: &String| mnemonic.as_str()) }
+
+    pub fn set_max_steps(&mut self, max_steps: usize) { self.max_steps = max_steps; }
+
+    /// Describes the registers, e.g. `ACC=0003 F=0 Z=1 PC=008 MAR=007 MDR=01000 IR=01000 steps=8`.
+    pub fn registers(&self) -> String {
+        format!(
+            "ACC={:04X} F={} Z={} PC={:03X} MAR={:03X} MDR={:05X} IR={:05X} steps={}",
+            self.acc, self.f as u8, self.z as u8, self.pc, self.mar, self.mdr, self.ir, self.steps
+        )
+    }
+
+    /// Sets how many microsteps are recorded so they can be undone with `back`. By default none are.
+    pub fn set_history_limit(&mut self, history_limit: usize) {
+        self.history_limit = history_limit;
+
+        while self.history.len() > history_limit {
+            self.history.pop_front();
+        }
+    }
+
+    /// Records the registers before a microstep, dropping the oldest record if the history is full.
+    fn record(&mut self) {
+        if self.history_limit == 0 {
+            return;
+        }
+
+        if self.history.len() == self.history_limit {
+            self.history.pop_front();
+        }
+
+        self.history.push_back(Record {
+            acc: self.acc,
+            f: self.f,
+            z: self.z,
+            pc: self.pc,
+            mar: self.mar,
+            mdr: self.mdr,
+            ir: self.ir,
+            phase: self.phase,
+            microstep: self.microstep,
+            address: self.address,
+            halted: self.halted,
+            steps: self.steps,
+            write: None,
+        });
+    }
+
+    /// Undoes the last recorded microstep, restoring the registers and the memory cell it wrote.
+    ///
+    /// ## Returns
+    ///
+    /// `false` if there is no recorded microstep to undo.
+    ///
+    pub fn back(&mut self) -> bool {
+        let Some(record) = self.history.pop_back() else {
+            return false;
+        };
+
+        if let Some((address, value)) = record.write {
+            self.memory[address] = value;
+        }
+
+        self.acc = record.acc;
+        self.f = record.f;
+        self.z = record.z;
+        self.pc = record.pc;
+        self.mar = record.mar;
+        self.mdr = record.mdr;
+        self.ir = record.ir;
+        self.phase = record.phase;
+        self.microstep = record.microstep;
+        self.address = record.address;
+        self.halted = record.halted;
+        self.steps = record.steps;
+
+        true
+    }
+
+    /// Updates F and the Z flag, which is set when F is clear.
+    fn set_f(&mut self, f: bool) {
+        self.f = f;
+        self.z = !f;
+    }
+
+    /// Runs the ALU operation of a control word, on ACC or on F.
+    ///
+    /// ## Arguments
+    /// - `word` - The control word
+    /// - `mdr` - The value of MDR before the word
+    ///
+    fn alu(&mut self, word: u64, mdr: u64) {
+        let operation: u64 = (word >> ALU_SHIFT) & ALU_MASK;
+        let carry: u64 = self.f as u64;
+        let data_max: u64 = self.machine.data_max();
+        let sign_bit: u32 = self.machine.data_bits() - 1;
+
+        if word & ALU_TO_F != 0 {
+            match operation {
+                6 => self.set_f(false),
+                7 => self.set_f(!self.f),
+                _ => {}
+            }
+            return;
+        }
+
+        match operation {
+            1 => self.acc = 0,
+            2 => self.acc = !self.acc & data_max,
+            3 => self.acc = (self.acc + 1) & data_max,
+            4 => self.acc = mdr & data_max,
+            5 => {
+                let sum: u64 = self.acc + (mdr & data_max);
+                self.acc = sum & data_max;
+                self.set_f(sum > data_max);
+            },
+            6 => {
+                let f: bool = self.acc >> sign_bit & 1 == 1;
+                self.acc = (self.acc << 1 | carry) & data_max;
+                self.set_f(f);
+            },
+            7 => {
+                let f: bool = self.acc & 1 == 1;
+                self.acc = self.acc >> 1 | carry << sign_bit;
+                self.set_f(f);
+            },
+            _ => {}
+        }
+    }
+
+    /// Ends the current phase: the fetch cycle dispatches to the microcode of the instruction in IR,
+    /// and the microcode goes back to the fetch cycle of the next instruction.
+    fn end_phase(&mut self) -> Result<(), SicompilerError> {
+        self.microstep = 0;
+
+        match self.phase {
+            Phase::Fetch => {
+                let opcode: u64 = self.machine.opcode(self.ir);
+
+                if opcode as usize >= self.microcode.len() {
+                    return Err(SicompilerError::InvalidOpcode { opcode, address: self.address });
+                }
+
+                self.phase = Phase::Execute(opcode as usize);
+            },
+            Phase::Execute(_) => {
+                self.phase = Phase::Fetch;
+                self.address = self.pc;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Runs one control word of the current phase.
+    ///
+    /// Every register loaded by a word takes the values the registers had before the word, like in a
+    /// clocked datapath, so `403100` swaps MDR and PC. An execute word of `0` halts the machine. A word
+    /// with `COND` ends the instruction without being applied if the Z flag is clear.
+    ///
+    /// ## Returns
+    ///
+    /// - `Result<(), Error>` - An `Error` if the instruction in IR is not in the repertoire,
+    ///   or the program ran more microsteps than allowed.
+    ///
+    pub fn micro_step(&mut self) -> Result<(), SicompilerError> {
+        if self.halted {
+            return Ok(());
+        }
+
+        if self.steps >= self.max_steps {
+            return Err(SicompilerError::StepLimitExceeded { steps: self.steps });
+        }
+
+        self.record();
+
+        let words: &Vec<u64> = match self.phase {
+            Phase::Fetch => &self.fetch,
+            Phase::Execute(opcode) => &self.microcode[opcode]
+        };
+
+        self.steps += 1;
+
+        let Some(&word) = words.get(self.microstep) else {
+            return self.end_phase();
+        };
+
+        if let Phase::Execute(_) = self.phase {
+            if word == 0 {
+                self.halted = true;
+                return Ok(());
+            }
+        }
+
+        if word & COND != 0 && !self.z {
+            return self.end_phase();
+        }
+
+        let (acc, pc, mar, mdr): (u64, u64, u64, u64) = (self.acc, self.pc, self.mar, self.mdr);
+        let address_max: u64 = self.machine.address_max();
+
+        match (word >> MAR_SOURCE_SHIFT) & FIELD_MASK {
+            1 => self.mar = pc,
+            2 => self.mar = mdr & address_max,
+            _ => {}
+        }
+
+        match (word >> MDR_SOURCE_SHIFT) & MDR_SOURCE_MASK {
+            1 => self.mdr = self.memory[mar as usize],
+            2 => self.mdr = acc,
+            3 => self.mdr = pc,
+            4 => {
+                self.mdr = (mdr + 1) & self.machine.data_max();
+                self.z = self.mdr == 0;
+            },
+            _ => {}
+        }
+
+        match (word >> MDR_TARGET_SHIFT) & FIELD_MASK {
+            1 => {
+                let address: usize = mar as usize;
+
+                if let Some(record) = self.history.back_mut() {
+                    record.write = Some((address, self.memory[address]));
+                }
+
+                self.memory[address] = mdr;
+            },
+            3 => self.ir = mdr,
+            _ => {}
+        }
+
+        self.alu(word, mdr);
+
+        if word & PC_FROM_MDR != 0 { self.pc = mdr & address_max; }
+        if word & PC_INC != 0 { self.pc = (pc + 1) & address_max; }
+
+        self.microstep += 1;
+
+        if word & FIN != 0 && word & COND == 0 {
+            return self.end_phase();
+        }
+
+        Ok(())
+    }
+
+    /// Runs microsteps until the current instruction ends, including its fetch cycle, or the machine halts.
+    pub fn step(&mut self) -> Result<(), SicompilerError> {
+        self.micro_step()?;
+
+        while !self.halted && (self.phase != Phase::Fetch || self.microstep != 0) {
+            self.micro_step()?;
+        }
+
+        Ok(())
+    }
+
+    /// Runs the program until the machine halts.
+    ///
+    /// ## Returns
+    ///
+    /// - `Result<(), Error>` - An `Error` if the program runs into a word that is not an instruction,
+    ///   or does not halt in the allowed number of microsteps.
+    ///
+    pub fn run(&mut self) -> Result<(), SicompilerError> {
+        while !self.halted {
+            self.micro_step()?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::models::{
+        variable::Variable,
+        label::Label,
+        init::Init,
+    };
+
+    use crate::structs::{assembler::ADDRESS_BITS, fixtures::{fetch, microcoded}};
+
+    use super::*;
+
+    fn repertoire() -> Repertoire {
+        Repertoire::new(fetch(), vec![
+            microcoded("HALT", false, &[0]),
+            microcoded("ADD", true, &[0x8000100, 0x1100, 0x28200]),
+            microcoded("CRA", false, &[0x8200]),
+            microcoded("CRF", false, &[0xB0200]),
+            microcoded("ROL_F_ACC", false, &[0x30200]),
+            microcoded("ROR_F_ACC", false, &[0x38200]),
+            microcoded("SFZ", false, &[0x200600]),
+            microcoded("JMP", true, &[0x400200]),
+            microcoded("STA", true, &[0x8000100, 0x2100, 0x1000200]),
+            microcoded("CTF", false, &[0xB8200]),
+            microcoded("ISZ", true, &[0x8000100, 0x1100, 0x4100, 0x1000100, 0x600, 0x200200]),
+            microcoded("CSR", true, &[0x8000100, 0x403100, 0x1000100, 0x200200]),
+        ])
+    }
+
+    fn build_program(variables: &[(&str, &str)], init: &str, instructions: &[(&str, Vec<&str>)]) -> Program {
+        Program::new(
+            variables.iter().map(|(dir, value): &(&str, &str)| Variable::new(dir, value)).collect(),
+            Init::new(init),
+            instructions.iter().map(|(mnemonic, params): &(&str, Vec<&str>)| {
+                let mut instruction: Instruction = Instruction::new(mnemonic, params.clone());
+                instruction.set_flag(!params.is_empty());
+                instruction
+            }).collect()
+        )
+    }
+
+    #[test]
+    fn test_new() {
+        let program: Program = build_program(&[("0", "0003"), ("1", "0004")], "6", &[("ADD", vec!["1"]), ("HALT", vec![])]);
+        let simulator: Simulator = Simulator::new(&program, &repertoire()).unwrap();
+
+        assert_eq!(simulator.memory()[0], 3);
+        assert_eq!(simulator.memory()[1], 4);
+        assert_eq!(simulator.memory()[6], 1 << ADDRESS_BITS | 1);
+        assert_eq!(simulator.memory()[7], 0);
+        assert_eq!(simulator.pc(), 6);
+        assert_eq!(simulator.phase(), Phase::Fetch);
+
+        let program: Program = build_program(&[], "FFF", &[("ADD", vec!["1"]), ("HALT", vec![])]);
+        let result: Result<Simulator, SicompilerError> = Simulator::new(&program, &repertoire());
+
+        assert!(result.is_err());
+        assert_eq!(result.unwrap_err().to_string(), "error[SC0019]: The address '1000' is out of the memory, the max is 'FFF'");
+    }
+
+    #[test]
+    fn test_run() {
+        let program: Program = build_program(&[("0", "0003"), ("1", "0004")], "6", &[
+            ("CRA", vec![]),
+            ("ADD", vec!["0"]),
+            ("ADD", vec!["1"]),
+            ("STA", vec!["4"]),
+            ("HALT", vec![])
+        ]);
+        let mut simulator: Simulator = Simulator::new(&program, &repertoire()).unwrap();
+
+        assert!(simulator.run().is_ok());
+        assert!(simulator.halted());
+        assert_eq!(simulator.acc(), 7);
+        assert_eq!(simulator.memory()[4], 7);
+        assert_eq!(simulator.address(), 0xA);
+    }
+
+    #[test]
+    fn test_sfz_jmp() {
+        //* Doubles the value in 0 if it is even, otherwise stores it unchanged
+        let program: Program = build_program(&[("0", "0003")], "6", &[
+            ("CRA", vec![]),
+            ("CRF", vec![]),
+            ("ADD", vec!["0"]),
+            ("ROR_F_ACC", vec![]),
+            ("SFZ", vec![]),
+            ("JMP", vec!["10"]),
+            ("ROL_F_ACC", vec![]),
+            ("ROL_F_ACC", vec![]),
+            ("STA", vec!["4"]),
+            ("HALT", vec![]),
+            ("ROL_F_ACC", vec![]),
+            ("STA", vec!["4"]),
+            ("HALT", vec![])
+        ]);
+        let mut simulator: Simulator = Simulator::new(&program, &repertoire()).unwrap();
+
+        assert!(simulator.run().is_ok());
+        assert_eq!(simulator.memory()[4], 3);
+
+        let program: Program = build_program(&[("0", "0002")], "6", &[
+            ("CRA", vec![]),
+            ("CRF", vec![]),
+            ("ADD", vec!["0"]),
+            ("ROR_F_ACC", vec![]),
+            ("SFZ", vec![]),
+            ("JMP", vec!["10"]),
+            ("ROL_F_ACC", vec![]),
+            ("ROL_F_ACC", vec![]),
+            ("STA", vec!["4"]),
+            ("HALT", vec![]),
+        ]);
+        let mut simulator: Simulator = Simulator::new(&program, &repertoire()).unwrap();
+
+        assert!(simulator.run().is_ok());
+        assert_eq!(simulator.memory()[4], 4);
+
+        let mut program: Program = build_program(&[("0", "0001")], "6", &[
+            ("CRA", vec![]),
+            ("JMP", vec!["SKIP"]),
+            ("ADD", vec!["0"]),
+            ("STA", vec!["4"]),
+            ("HALT", vec![])
+        ]);
+        program.set_labels(vec![Label::new("SKIP", 3)]);
+        let mut simulator: Simulator = Simulator::new(&program, &repertoire()).unwrap();
+
+        assert_eq!(simulator.memory()[7], 7 << ADDRESS_BITS | 9);
+        assert!(simulator.run().is_ok());
+        assert_eq!(simulator.memory()[4], 0);
+    }
+
+    #[test]
+    fn test_step() {
+        let program: Program = build_program(&[("0", "0003")], "6", &[("ADD", vec!["0"]), ("HALT", vec![])]);
+        let mut simulator: Simulator = Simulator::new(&program, &repertoire()).unwrap();
+
+        assert!(simulator.micro_step().is_ok());
+        assert_eq!(simulator.mar(), 6);
+        assert_eq!(simulator.microstep(), 1);
+
+        assert!(simulator.step().is_ok());
+        assert_eq!(simulator.acc(), 3);
+        assert_eq!(simulator.pc(), 7);
+        assert_eq!(simulator.address(), 7);
+        assert_eq!(simulator.steps(), 6);
+        assert_eq!(simulator.phase(), Phase::Fetch);
+
+        assert!(simulator.step().is_ok());
+        assert!(simulator.halted());
+    }
+
+    #[test]
+    fn test_fetch() {
+        //* The last fetch word loads IR from MDR and leaves the memory unchanged
+        let program: Program = build_program(&[("0", "0003")], "6", &[("ADD", vec!["0"]), ("HALT", vec![])]);
+        let mut simulator: Simulator = Simulator::new(&program, &repertoire()).unwrap();
+        let memory: Vec<u64> = simulator.memory().to_vec();
+
+        for _ in 0..3 {
+            assert!(simulator.micro_step().is_ok());
+        }
+
+        assert_eq!(simulator.ir(), 1 << ADDRESS_BITS);
+        assert_eq!(simulator.phase(), Phase::Execute(1));
+        assert_eq!(simulator.memory(), &memory);
+    }
+
+    #[test]
+    fn test_isz_csr_ctf() {
+        //* ISZ skips the HALT after it, CSR stores the return address in A and goes on at B
+        let program: Program = build_program(&[("0", "FFFF")], "6", &[
+            ("ISZ", vec!["0"]),
+            ("HALT", vec![]),
+            ("CSR", vec!["A"]),
+            ("HALT", vec![]),
+            ("HALT", vec![]),
+            ("CTF", vec![]),
+            ("HALT", vec![])
+        ]);
+        let mut simulator: Simulator = Simulator::new(&program, &repertoire()).unwrap();
+
+        assert!(simulator.run().is_ok());
+        assert_eq!(simulator.memory()[0], 0);
+        assert_eq!(simulator.memory()[0xA], 9);
+        assert_eq!(simulator.address(), 0xC);
+        assert!(simulator.f());
+    }
+
+    #[test]
+    fn test_back() {
+        let program: Program = build_program(&[("0", "0003")], "6", &[("ADD", vec!["0"]), ("STA", vec!["1"]), ("HALT", vec![])]);
+        let mut simulator: Simulator = Simulator::new(&program, &repertoire()).unwrap();
+
+        assert!(!simulator.back());
+
+        simulator.set_history_limit(100);
+
+        assert!(simulator.run().is_ok());
+        assert_eq!(simulator.memory()[1], 3);
+
+        while simulator.back() {}
+
+        assert_eq!(simulator.memory()[1], 0);
+        assert_eq!(simulator.acc(), 0);
+        assert_eq!(simulator.pc(), 6);
+        assert_eq!(simulator.steps(), 0);
+        assert!(!simulator.halted());
+
+        simulator.set_history_limit(2);
+
+        assert!(simulator.step().is_ok());
+        assert!(simulator.back());
+        assert!(simulator.back());
+        assert!(!simulator.back());
+        assert_eq!(simulator.steps(), 4);
+    }
+
+    #[test]
+    fn test_errors() {
+        //* A variable whose value is a valid opcode is run as an instruction
+        let program: Program = build_program(&[("0", "0003")], "6", &[("JMP", vec!["0"])]);
+        let mut simulator: Simulator = Simulator::new(&program, &repertoire()).unwrap();
+
+        assert!(simulator.run().is_ok());
+        assert_eq!(simulator.address(), 0);
+
+        let program: Program = build_program(&[("0", "F000")], "6", &[("JMP", vec!["0"])]);
+        let mut simulator: Simulator = Simulator::new(&program, &repertoire()).unwrap();
+        let result: Result<(), SicompilerError> = simulator.run();
+
+        assert_eq!(result.unwrap_err().to_string(), "error[SC0020]: The word at '0' has the opcode 15, which is not in the repertoire");
+
+        let program: Program = build_program(&[], "6", &[("JMP", vec!["6"])]);
+        let mut simulator: Simulator = Simulator::new(&program, &repertoire()).unwrap();
+        simulator.set_max_steps(100);
+        let result: Result<(), SicompilerError> = simulator.run();
+
+        assert_eq!(result.unwrap_err().to_string(), "error[SC0021]: The program did not halt after 100 microsteps");
+    }
+}
\ No newline at end of file
@@ -1,147 +1,334 @@
-use std::collections::HashMap;
-use std::{fs, io};
+use crate::models::{
+    instruction::Instruction,
+    variable::Variable,
+    init::Init,
+    program::Program,
+    span::Span,
+    source::SourceFile,
+    microprogram::{Microprogram, ControlWord},
+    repertoire::Repertoire,
+    operand::{Operand, OperandKind},
+    machine::Machine,
+    label::Label,
+    constant::Constant,
+    symbol::SymbolTable
+};
+use crate::errors::{error::SicompilerError, diagnostics::Diagnostics};
+use super::{assembler::{MAX_INSTRUCTIONS, MAX_ADDRESS_BITS, MAX_DATA_BITS, REGISTER_BITS}, repertoire_validator::RepertoireValidator, files};
 
-use crate::models::{instruction::Instruction, variable::Variable, init::Init, program::Program};
-use crate::errors::error::SicompilerError;
+/// The number of control signals of the SiCoMe datapath, the bits of the widest control word.
+pub const DEFAULT_CONTROL_WIDTH: u32 = 28;
+
+/// The characters a part of a line can end with when the expression it starts goes on in the next part.
+const EXPRESSION_OPEN: [char; 9] = ['+', '-', '*', '/', '&', '|', '<', '>', '('];
+
+/// The characters a part of a line can start with when it goes on with the expression of the previous part.
+const EXPRESSION_CLOSE: [char; 9] = ['+', '-', '*', '/', '&', '|', '<', '>', ')'];
 
 /// The `Tokenizer` struct is responsible for tokenizing input source code,
 /// removing comments and empty lines, and providing a sequence of valid code lines.
+///
+/// Comments are blanked out instead of removed, so every token keeps the byte offset
+/// it has in the original file and can be reported with its line and column.
 pub struct Tokenizer {
     input: String,
-    rep: String
+    rep: String,
+    control_width: u32,
+    address_bits: Option<u32>,
+    data_bits: Option<u32>
 }
 
 impl Tokenizer {
-    /// Remove one-line comments and empty lines from the input source code.
+    /// Replaces every character of `text` with spaces, keeping its length in bytes.
+    fn blank(text: &str) -> String {
+        " ".repeat(text.len())
+    }
+
+    /// Remove one-line comments from the input source code.
     /// 
     /// ## Arguments
     /// - `content` - The file content
     /// 
     /// ## Returns
-    /// A String with the new content without comments and empty lines.
+    /// A String with the comments replaced by blanks, so the offsets of the remaining code do not change.
     /// 
     fn remove_oneline_comments(content: &str) -> String {
-        content.lines()
-            .map(|line: &str| {
-                let trimmed_line: &str = line.trim();
-                if trimmed_line.is_empty() {
-                    String::from(line)
-                } else if let Some(index) = trimmed_line.find('#') {
-                    String::from(&trimmed_line[..index])
-                } else {
-                    String::from(trimmed_line)
-                }
+        content.split('\n')
+            .map(|line: &str| match line.find('#') {
+                Some(index) => format!("{}{}", &line[..index], Tokenizer::blank(&line[index..])),
+                None => String::from(line)
             })
             .collect::<Vec<String>>()
             .join("\n")
     }
 
-    /// Remove multi-line comments and empty lines from the input source code.
+    /// Remove multi-line comments from the input source code.
     /// 
     /// ## Arguments
     /// - `content` - The file content without one-line comments
     /// 
     /// ## Returns
-    /// A String with the new content without comments and empty lines.
+    /// A String with the comment lines replaced by blanks, so the offsets of the remaining code do not change.
     /// 
     fn remove_multiline_comments(content: &str) -> String {
-        let mut result: String = String::new();
         let mut in_comment: bool = false;
 
-        for line in content.lines() {
-            let trimmed_line: &str = line.trim();
+        content.split('\n')
+            .map(|line: &str| {
+                let trimmed_line: &str = line.trim();
 
-            if trimmed_line.starts_with("***") {
-                in_comment = true;
-                continue;
-            }
+                if trimmed_line.starts_with("***") {
+                    in_comment = true;
+                    return Tokenizer::blank(line);
+                }
 
-            if trimmed_line.ends_with("***") {
-                in_comment = false;
-                continue;
-            }
+                if trimmed_line.ends_with("***") {
+                    in_comment = false;
+                    return Tokenizer::blank(line);
+                }
+
+                if in_comment { Tokenizer::blank(line) } else { String::from(line) }
+            })
+            .collect::<Vec<String>>()
+            .join("\n")
+    }
+
+    /// Builds the span of a token.
+    /// 
+    /// ## Arguments
+    /// - `source` - The original file
+    /// - `code` - The file content without comments, with the same offsets as the original
+    /// - `token` - A slice of `code`
+    /// 
+    /// ## Returns
+    /// The `Span` of the token in the original file.
+    /// 
+    fn span(source: &SourceFile, code: &str, token: &str) -> Span {
+        let offset: usize = (token.as_ptr() as usize).saturating_sub(code.as_ptr() as usize);
+        source.span_at(offset, token.len())
+    }
 
-            if !in_comment {
-                result.push_str(line);
-                result.push('\n');
+    /// Joins the parts of a line that belong to the same expression, written with spaces around its
+    /// operators or parentheses, e.g. `table + 2`.
+    /// 
+    /// ## Arguments
+    /// - `code` - The file content without comments
+    /// - `parts` - The parts of a line split by whitespace, slices of `code`
+    /// 
+    /// ## Returns
+    /// The parts with every expression in a single slice of `code`, so its span covers it whole.
+    /// 
+    fn join_expressions<'a>(code: &'a str, parts: &[&'a str]) -> Vec<&'a str> {
+        let offset = |part: &str| (part.as_ptr() as usize).saturating_sub(code.as_ptr() as usize);
+        let mut joined: Vec<&'a str> = Vec::new();
+
+        for part in parts {
+            let continues: bool = joined.last().is_some_and(|last: &&str| last.ends_with(EXPRESSION_OPEN))
+                || part.starts_with(EXPRESSION_CLOSE);
+
+            match joined.last_mut() {
+                Some(last) if continues => *last = &code[offset(last)..offset(part) + part.len()],
+                _ => joined.push(part)
             }
         }
 
-        result
+        joined
+    }
+
+    /// Tokenizes a constant definition, written as `.equ NAME VALUE` or `NAME = VALUE`.
+    /// 
+    /// A line only defines a constant when its first word is `.equ` or is followed by `=`, so an `=`
+    /// anywhere else in an instruction or a variable is not taken as a definition.
+    /// 
+    /// ## Arguments
+    /// - `source` - The original file
+    /// - `code` - The file content without comments
+    /// - `token` - A line of the variables or the instructions section, without its labels
+    /// - `index` - The number of instructions written before the line
+    /// 
+    /// ## Returns
+    /// `None` if the line does not define a constant, or the `Constant` or an Error if it does.
+    /// 
+    fn tokenize_constant(source: &SourceFile, code: &str, token: &str, index: usize) -> Option<Result<Constant, SicompilerError>> {
+        let token: &str = token.trim();
+        let first: &str = token.split(|c: char| c.is_whitespace() || c == '=').next().unwrap_or_default();
+        let rest: &str = token[first.len()..].trim();
+
+        let definition: Option<(&str, &str)> = match (first, rest.strip_prefix('=')) {
+            (".equ", _) => rest.split_once(char::is_whitespace),
+            (name, Some(value)) => Some((name, value)),
+            _ => return None
+        };
+
+        let (name, value): (&str, &str) = match definition.map(|(name, value): (&str, &str)| (name.trim(), value.trim())) {
+            Some((name, value)) if !name.is_empty() && !value.is_empty() && !name.contains(char::is_whitespace) => (name, value),
+            _ => return Some(Err(SicompilerError::MalformedConstant { span: Tokenizer::span(source, code, token) }))
+        };
+
+        let span: Span = Tokenizer::span(source, code, name);
+
+        if !SymbolTable::is_valid_name(name) {
+            return Some(Err(SicompilerError::InvalidSymbolName { name: name.to_string(), span }));
+        }
+
+        let mut constant: Constant = Constant::new(name, value, index);
+        constant.set_spans(span, Tokenizer::span(source, code, value));
+
+        Some(Ok(constant))
     }
 
     /// Tokenizes instruction 
     /// 
+    /// A line can start with any number of labels, each one a name followed by `:`. A label names
+    /// the instruction written after it, in the same line or in the next ones. An operand can be an
+    /// expression with spaces between its operators. A line can also define a constant instead.
+    /// 
     /// ## Arguments
+    /// - `source` - The original file
+    /// - `code` - The file content without comments
     /// - `section` - The instruction section of the file
+    /// - `diagnostics` - The collector where invalid label names are reported
     /// 
     /// ## Returns 
-    /// A vector of `Instruction` instances.
-    /// 
-    fn tokenize_instructions(section: &str) -> Vec<Instruction> {
-        section
-            .lines()
-            .filter(|token| !token.is_empty())
-            .map(|token| {
-                let parts: Vec<&str> = token.split_whitespace().collect();
-                Instruction::new(parts[0], parts[1..].to_vec())
-            })
-            .collect()
+    /// The `Instruction`, `Label` and `Constant` instances, skipping the invalid labels and constants.
+    /// 
+    fn tokenize_instructions(source: &SourceFile, code: &str, section: &str, diagnostics: &mut Diagnostics) -> (Vec<Instruction>, Vec<Label>, Vec<Constant>) {
+        let mut instructions: Vec<Instruction> = Vec::new();
+        let mut labels: Vec<Label> = Vec::new();
+        let mut constants: Vec<Constant> = Vec::new();
+
+        for token in section.lines() {
+            let mut parts: Vec<&str> = token.split_whitespace().collect();
+
+            while let Some(name) = parts.first().and_then(|part: &&str| part.strip_suffix(':')) {
+                let span: Span = Tokenizer::span(source, code, &parts[0][..name.len()]);
+
+                if SymbolTable::is_valid_name(name) {
+                    let mut label: Label = Label::new(name, instructions.len());
+                    label.set_span(span);
+                    labels.push(label);
+                } else {
+                    diagnostics.push(SicompilerError::InvalidSymbolName { name: name.to_string(), span });
+                }
+
+                parts.remove(0);
+            }
+
+            if parts.is_empty() { continue }
+
+            //* The labels before a constant still name the next instruction
+            let rest: &str = &token[(parts[0].as_ptr() as usize - token.as_ptr() as usize)..];
+
+            if let Some(result) = Tokenizer::tokenize_constant(source, code, rest, instructions.len()) {
+                match result {
+                    Ok(constant) => constants.push(constant),
+                    Err(err) => diagnostics.push(err)
+                }
+                continue;
+            }
+
+            let params: Vec<&str> = Tokenizer::join_expressions(code, &parts[1..]);
+
+            let mut instruction: Instruction = Instruction::new(parts[0], params.clone());
+            instruction.set_span(Tokenizer::span(source, code, parts[0]));
+            instruction.set_param_spans(params.iter().map(|part: &&str| Tokenizer::span(source, code, part)).collect());
+
+            instructions.push(instruction);
+        }
+
+        (instructions, labels, constants)
     }
 
     /// Tokenizes varibles 
     /// 
+    /// Each variable is its dir, its initial value and, optionally, a name the instructions can use instead of the dir.
+    /// A line can also define a constant instead.
+    /// 
     /// ## Arguments
+    /// - `source` - The original file
+    /// - `code` - The file content without comments
     /// - `section` - The varibles section of the file
+    /// - `diagnostics` - The collector where malformed lines are reported
     /// 
     /// ## Returns 
-    /// A vector of `Varibles` instances or an Error.
+    /// The `Variable` and `Constant` instances, skipping the malformed lines.
     /// 
-    fn tokenize_variables(section: &str) -> Result<Vec<Variable>, SicompilerError> {
+    fn tokenize_variables(source: &SourceFile, code: &str, section: &str, diagnostics: &mut Diagnostics) -> (Vec<Variable>, Vec<Constant>) {
         let mut variables: Vec<Variable> = Vec::new();
+        let mut constants: Vec<Constant> = Vec::new();
         
         for token in section.lines() {
+            let token: &str = token.trim();
+
             if token.is_empty() { continue }
+
+            if let Some(result) = Tokenizer::tokenize_constant(source, code, token, 0) {
+                match result {
+                    Ok(constant) => constants.push(constant),
+                    Err(err) => diagnostics.push(err)
+                }
+                continue;
+            }
     
-            let parts: Vec<&str> = token.split_whitespace().collect();
+            let parts: Vec<&str> = Tokenizer::join_expressions(code, &token.split_whitespace().collect::<Vec<&str>>());
             
-            if parts.len() != 2 { 
-                return Err(
-                    SicompilerError::TokenizationError(format!("Invalid variable format, the correct way is <DIR NAME>"))
-                );
+            if parts.len() != 2 && parts.len() != 3 { 
+                diagnostics.push(SicompilerError::InvalidVariableFormat { span: Tokenizer::span(source, code, token) });
+                continue;
             }
 
-            variables.push(Variable::new(parts[0], parts[1]));
+            let mut variable: Variable = Variable::new(parts[0], parts[1]);
+            variable.set_spans(Tokenizer::span(source, code, parts[0]), Tokenizer::span(source, code, parts[1]));
+
+            if let Some(symbol) = parts.get(2) {
+                let span: Span = Tokenizer::span(source, code, symbol);
+
+                if !SymbolTable::is_valid_name(symbol) {
+                    diagnostics.push(SicompilerError::InvalidSymbolName { name: symbol.to_string(), span });
+                    continue;
+                }
+
+                variable.set_symbol(symbol, span);
+            }
+
+            variables.push(variable);
         }
 
-        Ok(variables)
+        (variables, constants)
     }
 
     /// Tokenizes init section 
     /// 
     /// ## Arguments
+    /// - `source` - The original file
+    /// - `code` - The file content without comments
     /// - `section` - The init section of the file
     /// 
     /// ## Returns 
     /// A `Init` instances or an Error.
     /// 
-    fn tokenize_init(section: &str) -> Result<Init, SicompilerError> {
+    fn tokenize_init(source: &SourceFile, code: &str, section: &str) -> Result<Init, SicompilerError> {
         if section.is_empty() { 
-            return Err(SicompilerError::TokenizationError(format!("There is no any Init section.")));
+            return Err(SicompilerError::MissingInitAddress { span: Tokenizer::span(source, code, section) });
         }
 
         let valid_section: Vec<&str> = section.split_whitespace().collect();
 
         if valid_section.is_empty() { 
-            return Err(SicompilerError::TokenizationError(format!("There is no any Init address.")));
+            return Err(SicompilerError::MissingInitAddress { span: Tokenizer::span(source, code, &section[..0]) });
         }
 
         if valid_section.len() > 1 {
-            return Err(SicompilerError::TokenizationError(format!("There is more than one Init address.")));
+            return Err(SicompilerError::DuplicateInitAddress {
+                first: valid_section[0].to_string(),
+                span: Tokenizer::span(source, code, valid_section[1])
+            });
         }
 
-        let dir: &str = valid_section[0];
-        Ok(Init::new(dir))
+        let mut init: Init = Init::new(valid_section[0]);
+        init.set_span(Tokenizer::span(source, code, valid_section[0]));
+
+        Ok(init)
     }
     
     /// Creates a new `Tokenizer` instance with the specified input file name.
@@ -151,32 +338,247 @@ impl Tokenizer {
     /// - `input` - The name of the input file to be tokenized.
     /// 
     pub fn new(input: &str, rep: &str) -> Tokenizer { 
-        Tokenizer { input: input.to_string(), rep: rep.to_string() }
+        Tokenizer { input: input.to_string(), rep: rep.to_string(), control_width: DEFAULT_CONTROL_WIDTH, address_bits: None, data_bits: None }
+    }
+
+    /// Sets the number of control signals the control words of the repertoire are checked against.
+    pub fn set_control_width(&mut self, control_width: u32) {
+        self.control_width = control_width;
+    }
+
+    /// Sets the widths of the machine, which take precedence over the ones of the repertoire header.
+    pub fn set_widths(&mut self, address_bits: Option<u32>, data_bits: Option<u32>) {
+        self.address_bits = address_bits;
+        self.data_bits = data_bits;
+    }
+
+    /// Tokenizes the header of a repertoire, the lines before the microprogram section that declare the widths of the machine.
+    /// 
+    /// ## Arguments
+    /// - `source` - The repertoire file
+    /// - `code` - The content of the repertoire
+    /// - `section` - The header
+    /// - `diagnostics` - The collector where malformed lines and invalid widths are reported
+    /// 
+    /// ## Returns
+    /// The `Machine` with the declared widths, and the default ones for the others.
+    /// 
+    fn tokenize_header(source: &SourceFile, code: &str, section: &str, diagnostics: &mut Diagnostics) -> Machine {
+        let mut machine: Machine = Machine::default();
+
+        for token in section.lines() {
+            let token: &str = token.trim();
+
+            if token.is_empty() { continue }
+
+            let parts: Vec<&str> = token.split_whitespace().collect();
+
+            let (name, max): (&str, u32) = match parts[..] {
+                ["ADDRESS", _] => ("address", MAX_ADDRESS_BITS),
+                ["DATA", _] => ("data", MAX_DATA_BITS),
+                _ => {
+                    diagnostics.push(SicompilerError::MalformedHeaderLine { span: Tokenizer::span(source, code, token) });
+                    continue;
+                }
+            };
+
+            let Some(width) = parts[1].parse::<u32>().ok().filter(|width: &u32| (1..=max).contains(width)) else {
+                diagnostics.push(SicompilerError::InvalidWidth {
+                    name: name.to_string(),
+                    width: parts[1].to_string(),
+                    max,
+                    span: Tokenizer::span(source, code, parts[1])
+                });
+                continue;
+            };
+
+            match name {
+                "address" => machine.set_address_bits(width),
+                _ => machine.set_data_bits(width)
+            }
+        }
+
+        machine
     }
 
-    /// Tokenizes the content of a repertoire file, creating a mapping of mnemonics to instructions.
+    /// Tokenizes a control word of the repertoire, checking it is in hex base and fits in the control signals.
+    /// 
+    /// ## Arguments
+    /// - `source` - The repertoire file
+    /// - `code` - The content of the repertoire
+    /// - `token` - The control word
+    /// - `control_width` - The number of control signals
+    /// 
+    /// ## Returns
+    /// A `ControlWord` instance or an Error.
+    /// 
+    fn tokenize_control_word(source: &SourceFile, code: &str, token: &str, control_width: u32) -> Result<ControlWord, SicompilerError> {
+        let span: Span = Tokenizer::span(source, code, token);
+
+        if !token.chars().all(|c: char| c.is_ascii_hexdigit()) {
+            return Err(SicompilerError::NonHexControlWord { word: token.to_string(), span });
+        }
+
+        let value: Option<u64> = u64::from_str_radix(token, 16).ok()
+            .filter(|value: &u64| control_width >= u64::BITS || value >> control_width == 0);
+
+        let Some(value) = value else {
+            return Err(SicompilerError::ControlWordTooWide { word: token.to_string(), width: control_width, span });
+        };
+
+        Ok(ControlWord::new(value, span))
+    }
+
+    /// Tokenizes the operand signature of a repertoire instruction.
+    /// 
+    /// The signature is `false` for an instruction without operands, `true` for one address operand, or the list
+    /// of its operands separated by `,`, each one its kind (`addr`, `imm` or `reg`) and, optionally, its width in
+    /// bits after `:`, e.g. `reg:4,imm:8`. The operands are packed in the operand field of the encoded word, so
+    /// their widths can not add up to more than the address bits of the machine.
+    /// 
+    /// ## Arguments
+    /// - `source` - The repertoire file
+    /// - `code` - The content of the repertoire
+    /// - `token` - The signature
+    /// - `machine` - The machine, which gives the width of the operand field
+    /// 
+    /// ## Returns
+    /// The operands of the instruction or an Error.
+    /// 
+    fn tokenize_signature(source: &SourceFile, code: &str, token: &str, machine: &Machine) -> Result<Vec<Operand>, SicompilerError> {
+        let span: Span = Tokenizer::span(source, code, token);
+        let address_bits: u32 = machine.address_bits();
+
+        match token {
+            "true" => return Ok(vec![Operand::new(OperandKind::Address, address_bits)]),
+            "false" => return Ok(vec![]),
+            _ => {}
+        }
+
+        let invalid = || SicompilerError::InvalidSignature { signature: token.to_string(), span: span.clone() };
+
+        let operands: Vec<Operand> = token.split(',')
+            .map(|operand: &str| {
+                let (name, width): (&str, Option<&str>) = match operand.split_once(':') {
+                    Some((name, width)) => (name, Some(width)),
+                    None => (operand, None)
+                };
+
+                let kind: OperandKind = OperandKind::from_name(name).ok_or_else(invalid)?;
+                let width: u32 = match width {
+                    Some(width) => width.parse::<u32>().ok().filter(|width: &u32| *width > 0).ok_or_else(invalid)?,
+                    None if kind == OperandKind::Register => REGISTER_BITS,
+                    None => address_bits
+                };
+
+                //* An operand wider than the whole field is reported on its own, before its width is added to the others
+                if width > address_bits {
+                    return Err(SicompilerError::SignatureTooWide {
+                        signature: operand.to_string(),
+                        width,
+                        max: address_bits,
+                        span: Tokenizer::span(source, code, operand)
+                    });
+                }
+
+                Ok(Operand::new(kind, width))
+            })
+            .collect::<Result<Vec<Operand>, SicompilerError>>()?;
+
+        let width: Option<u32> = operands.iter().try_fold(0u32, |total: u32, operand: &Operand| total.checked_add(operand.width()));
+
+        match width {
+            Some(width) if width <= address_bits => {},
+            width => return Err(SicompilerError::SignatureTooWide {
+                signature: token.to_string(),
+                width: width.unwrap_or(u32::MAX),
+                max: address_bits,
+                span
+            })
+        }
+
+        Ok(operands)
+    }
+
+    /// Tokenizes the microprogram section of a repertoire.
+    /// 
+    /// ## Arguments
+    /// - `source` - The repertoire file
+    /// - `code` - The content of the repertoire
+    /// - `section` - The microprogram section
+    /// - `control_width` - The number of control signals
+    /// - `diagnostics` - The collector where malformed lines and control words are reported
+    /// 
+    /// ## Returns
+    /// A `Microprogram` with the control words of the fetch cycle, skipping the invalid ones.
+    /// 
+    fn tokenize_microprogram(source: &SourceFile, code: &str, section: &str, control_width: u32, diagnostics: &mut Diagnostics) -> Microprogram {
+        let mut fetch: Vec<ControlWord> = Vec::new();
+
+        for token in section.lines() {
+            let token: &str = token.trim();
+
+            if token.is_empty() { continue }
+
+            let parts: Vec<&str> = token.split_whitespace().collect();
+
+            if parts.len() != 2 || parts[0] != "CB" {
+                diagnostics.push(SicompilerError::MalformedMicroprogramLine { span: Tokenizer::span(source, code, token) });
+                continue;
+            }
+
+            match Tokenizer::tokenize_control_word(source, code, parts[1], control_width) {
+                Ok(word) => fetch.push(word),
+                Err(err) => diagnostics.push(err)
+            }
+        }
+
+        Microprogram::new(fetch)
+    }
+
+    /// Tokenizes the content of a repertoire, creating the microprogram and the list of valid instructions.
     ///
-    /// # Arguments
+    /// ## Arguments
     ///
-    /// - `repertoire_input` - A string representing the path to the repertoire file.
+    /// - `source` - The repertoire file.
+    /// - `control_width` - The number of control signals.
+    /// - `address_bits` - The address bits of the machine, overriding the ones of the header if given.
+    /// - `data_bits` - The data bits of the machine, overriding the ones of the header if given.
+    /// - `diagnostics` - The collector where malformed lines, invalid flags and control words, and the
+    ///   problems found by the `RepertoireValidator` are reported.
     ///
-    /// # Returns
+    /// ## Returns
     ///
-    /// - `Result<HashMap<String, Instruction>, Error>` - Result containing a mapping of mnemonics to instructions
-    ///   if successful, or an `Error` if any issues occur during tokenization or file reading.
+    /// - `Result<Repertoire, Error>` - Result containing the repertoire if it could be tokenized, skipping the malformed lines,
+    ///   or an `Error` if its structure is invalid.
     ///
-    pub fn tokenize_repertoire(&self) -> Result<HashMap<String, Instruction>, SicompilerError> {
-        let mut repertorie: HashMap<String, Instruction> = HashMap::new();
+    pub(crate) fn tokenize_repertoire_source(source: &SourceFile, control_width: u32, address_bits: Option<u32>, data_bits: Option<u32>, diagnostics: &mut Diagnostics) -> Result<Repertoire, SicompilerError> {
+        let mut instructions: Vec<Instruction> = Vec::new();
         
-        let content: String = fs::read_to_string(&self.rep)?;
+        let code: &str = source.content();
+        let content: Vec<&str> = code.split('$').collect();
+
+        match content.len() {
+            1 => return Err(SicompilerError::MissingMicroprogram { span: Span::file_only(source.path()) }),
+            2 => return Err(SicompilerError::MissingMicroprogram { span: Tokenizer::span(source, code, &code[content[0].len()..content[0].len() + 1]) }),
+            3 => {},
+            _ => {
+                let separator: usize = content[0].len() + content[1].len() + content[2].len() + 2;
+                return Err(SicompilerError::ExtraRepertoireSeparator { span: Tokenizer::span(source, code, &code[separator..separator + 1]) });
+            }
+        }
+
+        let mut machine: Machine = Tokenizer::tokenize_header(source, code, content[0], diagnostics);
 
-        if !content.contains("$") {
-            return Err(SicompilerError::TokenizationError(
-                format!("Invalid repertoire structure, the file must contain a microprogram section.")
-            ));
+        if let Some(address_bits) = address_bits {
+            machine.set_address_bits(address_bits);
         }
 
-        let content: Vec<&str> = content.split('$').collect();
+        if let Some(data_bits) = data_bits {
+            machine.set_data_bits(data_bits);
+        }
+
+        let microprogram: Microprogram = Tokenizer::tokenize_microprogram(source, code, content[1], control_width, diagnostics);
 
         let mut instructions_part: &str = content[2];
 
@@ -184,102 +586,184 @@ impl Tokenizer {
             instructions_part = &instructions_part[1..];
         }
 
-        if instructions_part.lines().count() > 32 {
-            return Err(SicompilerError::TokenizationError(
-                format!("Invalid number of instructions, the max is 32 but get {}", instructions_part.lines().count())
-            ));
+        if instructions_part.lines().count() > MAX_INSTRUCTIONS {
+            let extra_line: &str = instructions_part.lines().nth(MAX_INSTRUCTIONS).unwrap_or_default();
+
+            return Err(SicompilerError::RepertoireTooLarge {
+                found: instructions_part.lines().count(),
+                max: MAX_INSTRUCTIONS,
+                span: Tokenizer::span(source, code, extra_line)
+            });
         }
 
         for token in instructions_part.lines() {
+            let token: &str = token.trim();
+
             if token.is_empty() { continue }
             
             let parts: Vec<&str> = token.split_whitespace().collect();
 
-            let mnemonic: String = parts[0].to_string();
-            let flag: bool = parts[1] == "true";
+            let Some(signature) = parts.get(1) else {
+                diagnostics.push(SicompilerError::MalformedInstructionLine { span: Tokenizer::span(source, code, token) });
+                continue;
+            };
 
-            let mut instruction: Instruction = Instruction::new(&mnemonic, vec![]);
+            let operands: Vec<Operand> = match Tokenizer::tokenize_signature(source, code, signature, &machine) {
+                Ok(operands) => operands,
+                Err(err) => {
+                    diagnostics.push(err);
+                    continue;
+                }
+            };
 
-            if flag {
-                instruction.set_flag(true);
-                
-                //* An instruction only have 1 argument  
-                instruction.set_params(vec!["0x123"]);
-            }
+            let mut instruction: Instruction = Instruction::new(parts[0], vec![]);
+            instruction.set_span(Tokenizer::span(source, code, parts[0]));
+            instruction.set_operands(operands);
+
+            let microcode: Vec<ControlWord> = parts[2..]
+                .iter()
+                .filter_map(|word: &&str| Tokenizer::tokenize_control_word(source, code, word, control_width)
+                    .map_err(|err: SicompilerError| diagnostics.push(err))
+                    .ok())
+                .collect();
 
-            repertorie.insert(mnemonic, instruction);
+            instruction.set_microcode(microcode);
+
+            instructions.push(instruction);
+        }
+
+        let mut repertoire: Repertoire = Repertoire::new(microprogram, instructions);
+        repertoire.set_machine(machine);
+        repertoire.set_path(source.path());
+        RepertoireValidator::new(&repertoire, source.path()).validate(diagnostics);
+
+        Ok(repertoire)
+    }
+
+    /// Tokenizes the content of a program.
+    /// 
+    /// ## Arguments
+    /// 
+    /// - `source` - The program file.
+    /// - `diagnostics` - The collector where recoverable problems are reported.
+    /// 
+    /// ## Returns
+    /// 
+    /// - `Result<Program, Error>` - Result containing a `Program` instance, or an `Error` if the file can't be tokenized at all.
+    /// 
+    pub(crate) fn tokenize_source(source: &SourceFile, diagnostics: &mut Diagnostics) -> Result<Program, SicompilerError> {
+        let content: &str = source.content();
+
+        if content.is_empty() { 
+            return Err(SicompilerError::EmptyFile { span: Span::file_only(source.path()) });
+        }
+
+        let mut code: String = Tokenizer::remove_oneline_comments(content);
+        code = Tokenizer::remove_multiline_comments(&code);
+
+        let sections: Vec<&str> = code.split('@').collect();
+
+        if sections.len() != 3 {
+            return Err(match sections.get(3) {
+                Some(extra_section) => {
+                    let separator: usize = (extra_section.as_ptr() as usize - code.as_ptr() as usize) - 1;
+                    let span: Span = Tokenizer::span(source, &code, &code[separator..separator + 1]);
+
+                    SicompilerError::TooManySections { found: sections.len(), span }
+                },
+                None => SicompilerError::MissingSection { found: sections.len(), span: Span::file_only(source.path()) }
+            });
         }
         
-        Ok(repertorie)
+        let (variables, mut constants): (Vec<Variable>, Vec<Constant>) = Tokenizer::tokenize_variables(source, &code, sections[0], diagnostics);
+        let init: Init = Tokenizer::tokenize_init(source, &code, sections[1]).unwrap_or_else(|err: SicompilerError| {
+            diagnostics.push(err);
+            Init::new("")
+        });
+        let (instructions, labels, instruction_constants): (Vec<Instruction>, Vec<Label>, Vec<Constant>) = Tokenizer::tokenize_instructions(source, &code, sections[2], diagnostics);
+
+        constants.extend(instruction_constants);
+
+        let mut program: Program = Program::new(variables, init, instructions);
+        program.set_span(Span::file_only(source.path()));
+        program.set_labels(labels);
+        program.set_constants(constants);
+
+        Ok(program)
+    }
+
+    /// Tokenizes the content of a repertoire file, creating the microprogram and the list of valid instructions.
+    ///
+    /// Invalid control words are pushed to `diagnostics`. The file is also added to `diagnostics`,
+    /// so errors pointing to it can show its source lines.
+    ///
+    /// ## Arguments
+    ///
+    /// - `&self` - Reference to the `Tokenizer` instance.
+    /// - `diagnostics` - The collector where recoverable problems are reported.
+    ///
+    /// ## Returns
+    ///
+    /// - `Result<Repertoire, Error>` - Result containing the repertoire if successful,
+    ///   or an `Error` if any issues occur during tokenization or file reading.
+    ///
+    pub fn tokenize_repertoire(&self, diagnostics: &mut Diagnostics) -> Result<Repertoire, SicompilerError> {
+        let source: SourceFile = SourceFile::new(files::display_name(&self.rep), &files::read_to_string(&self.rep)?);
+        let result: Result<Repertoire, SicompilerError> = Tokenizer::tokenize_repertoire_source(&source, self.control_width, self.address_bits, self.data_bits, diagnostics);
+
+        diagnostics.add_source(source);
+
+        result
     }
     
     /// Tokenizes the content of the input file and returns a `Result` containing a `Program` or an `Error`.
+    /// The program is read from the standard input if the input path is `-`.
+    /// 
+    /// Problems that still allow building a `Program`, like a malformed variable or a bad init section,
+    /// are pushed to `diagnostics` so the rest of the file can still be checked. The file is also added
+    /// to `diagnostics`, so errors pointing to it can show its source lines.
     /// 
     /// ## Arguments
     /// 
     /// - `&self` - Reference to the `Tokenizer` instance.
+    /// - `diagnostics` - The collector where recoverable problems are reported.
     /// 
     /// ## Returns
     /// 
-    /// - `Result<Program, Error>` - Result containing a `Program` instance if successful, or an `Error` if any issues occur.
+    /// - `Result<Program, Error>` - Result containing a `Program` instance, or an `Error` if the file can't be tokenized at all.
     /// 
     /// ## Errors
     /// 
     /// Returns an `Error` if:
     /// 
+    /// - The file can't be read.
     /// - The file is empty.
     /// - The number of sections in the file is not equal to 3.
-    /// - No init dir is found.
     /// 
-    pub fn tokenize(&self) -> Result<Program, SicompilerError> {
-        let mut content: String = fs::read_to_string(&self.input)
-            .map_err(|err: io::Error| 
-                SicompilerError::Io(io::Error::new(err.kind(), format!("Can't open {}", self.input)))
-            )?;
-        
-        if content.is_empty() { 
-            return Err(SicompilerError::TokenizationError(format!("The file is empty")));
-        }
-
-        content = Tokenizer::remove_oneline_comments(&content);
-        content = Tokenizer::remove_multiline_comments(&content);
+    pub fn tokenize(&self, diagnostics: &mut Diagnostics) -> Result<Program, SicompilerError> {
+        let content: String = files::read_to_string(&self.input)?;
+        let source: SourceFile = SourceFile::new(files::display_name(&self.input), &content);
+        let result: Result<Program, SicompilerError> = Tokenizer::tokenize_source(&source, diagnostics);
 
-        let sections: Vec<&str> = content.split('@').collect();
+        diagnostics.add_source(source);
 
-        if sections.len() != 3 {
-            return Err(SicompilerError::TokenizationError(
-                format!("Invalid number of sections, must be 3 but get {}", sections.len())
-            ))
-        }
-        
-        let mut variables: Vec<Variable> = Vec::new();
-        if let Some(variable_section) = sections.get(0) {
-            variables = Tokenizer::tokenize_variables(variable_section)?;
-        }
-        
-        let mut init: Init = Init::new("");
-        if let Some(init_section) = sections.get(1) {
-            init = Tokenizer::tokenize_init(init_section)?;
-        }
-        
-        let mut instructions: Vec<Instruction> = Vec::new();
-        if let Some(instruction_section) = sections.get(2) {
-            instructions = Tokenizer::tokenize_instructions(instruction_section);
-        }
-
-        Ok(Program::new(variables, init, instructions))
+        result
     }
 }
 
 #[cfg(test)]
 mod tests {
-    use std::collections::HashMap;
-
     use crate::models::{
         instruction::Instruction, 
         variable::Variable, 
         init::Init, 
-        program::Program
+        program::Program,
+        label::Label,
+        span::Span,
+        source::SourceFile,
+        operand::{Operand, OperandKind},
+        microprogram::Microprogram,
+        repertoire::Repertoire
     };
 
     use crate::errors::error::SicompilerError;
@@ -293,7 +777,13 @@ This is a line with a comment #This is a one-line comment
 Another line without comments";
 
         let result: String = Tokenizer::remove_oneline_comments(content);
-        assert_eq!(result, "This is a line without comments\nThis is a line with a comment \nAnother line without comments");
+        let expected: String = format!(
+            "This is a line without comments\nThis is a line with a comment {}\nAnother line without comments",
+            " ".repeat("#This is a one-line comment".len())
+        );
+
+        assert_eq!(result, expected);
+        assert_eq!(result.len(), content.len());
     }
 
     #[test]
@@ -305,120 +795,329 @@ comment ***
 Another line without comments.";
     
         let result: String = Tokenizer::remove_multiline_comments(content);
-        assert_eq!(result, "This is a line without comments.\nAnother line without comments.\n");
+        let lines: Vec<&str> = result.lines().map(|line: &str| line.trim()).collect();
+
+        assert_eq!(lines, vec!["This is a line without comments.", "", "", "", "Another line without comments."]);
+        assert_eq!(result.len(), content.len());
     }
 
     #[test]
     fn test_tokenize_instructions() {
-        let section: &str = "HALT\nADD 1";
-        let instructions: Vec<Instruction> = Tokenizer::tokenize_instructions(section);
+        let source: SourceFile = SourceFile::new("test.txt", "HALT\nADD 1");
+        let (instructions, labels, _): (Vec<Instruction>, Vec<Label>, Vec<Constant>) = Tokenizer::tokenize_instructions(&source, source.content(), source.content(), &mut Diagnostics::new());
 
         assert_eq!(instructions.len(), 2);
+        assert!(labels.is_empty());
         assert_eq!(instructions[0].mnemonic(), "HALT");
         assert_eq!(instructions[0].params().len(), 0);
         assert_eq!(instructions[1].mnemonic(), "ADD");
         assert_eq!(instructions[1].params().len(), 1);
+        assert_eq!(instructions[1].span(), &Span::new("test.txt", 2, 1, 3));
+        assert_eq!(instructions[1].param_span(0), &Span::new("test.txt", 2, 5, 1));
+    }
+
+    #[test]
+    fn test_tokenize_expressions() {
+        let source: SourceFile = SourceFile::new("test.txt", "ADD table + 2\nJMP $-3\nLDI R1 ( count + 1 ) * 2");
+        let (instructions, _, _): (Vec<Instruction>, Vec<Label>, Vec<Constant>) = Tokenizer::tokenize_instructions(&source, source.content(), source.content(), &mut Diagnostics::new());
+
+        assert_eq!(instructions[0].params(), &vec!["table + 2".to_string()]);
+        assert_eq!(instructions[0].param_span(0), &Span::new("test.txt", 1, 5, 9));
+        assert_eq!(instructions[1].params(), &vec!["$-3".to_string()]);
+        assert_eq!(instructions[2].params(), &vec!["R1".to_string(), "( count + 1 ) * 2".to_string()]);
+
+        let source: SourceFile = SourceFile::new("test.txt", "0 table + 1 next");
+        let (variables, _): (Vec<Variable>, Vec<Constant>) = Tokenizer::tokenize_variables(&source, source.content(), source.content(), &mut Diagnostics::new());
+
+        assert_eq!(variables[0].value(), "table + 1");
+        assert_eq!(variables[0].symbol(), Some("next"));
+    }
+
+    #[test]
+    fn test_tokenize_constants() {
+        let source: SourceFile = SourceFile::new("test.txt", ".equ TABLE 20\nSIZE = TABLE + 4\n0 SIZE\n.equ\n= 3\n2X = 1");
+        let mut diagnostics: Diagnostics = Diagnostics::new();
+        let (variables, constants): (Vec<Variable>, Vec<Constant>) = Tokenizer::tokenize_variables(&source, source.content(), source.content(), &mut diagnostics);
+
+        assert_eq!(variables.len(), 1);
+        assert_eq!(constants.len(), 2);
+        assert_eq!((constants[0].name(), constants[0].value()), ("TABLE", "20"));
+        assert_eq!((constants[1].name(), constants[1].value()), ("SIZE", "TABLE + 4"));
+        assert_eq!(constants[1].span(), &Span::new("test.txt", 2, 1, 4));
+        assert_eq!(constants[1].value_span(), &Span::new("test.txt", 2, 8, 9));
+        assert_eq!(diagnostics.len(), 3);
+        assert_eq!(diagnostics.errors()[0].to_string(), "test.txt:4:1: error[SC0042]: Invalid constant format, the correct way is <.equ NAME VALUE> or <NAME = VALUE>");
+        assert_eq!(diagnostics.errors()[1].code(), "SC0042");
+        assert_eq!(diagnostics.errors()[2].code(), "SC0024");
+
+        let source: SourceFile = SourceFile::new("test.txt", "ADD 1\nHERE = $\nLOOP: STEP=2\nADD x = 3\nHALT");
+        let mut diagnostics: Diagnostics = Diagnostics::new();
+        let (instructions, labels, constants): (Vec<Instruction>, Vec<Label>, Vec<Constant>) = Tokenizer::tokenize_instructions(&source, source.content(), source.content(), &mut diagnostics);
+
+        assert!(diagnostics.is_empty());
+        assert_eq!(instructions.len(), 3);
+        assert_eq!(instructions[1].params(), &vec!["x".to_string(), "=".to_string(), "3".to_string()]);
+        assert_eq!(constants[0].name(), "HERE");
+        assert_eq!(constants[0].index(), 1);
+        assert_eq!((constants[1].name(), constants[1].value()), ("STEP", "2"));
+        assert_eq!(constants[1].span(), &Span::new("test.txt", 3, 7, 4));
+        assert_eq!((labels[0].name(), labels[0].index()), ("LOOP", 1));
+    }
+
+    #[test]
+    fn test_tokenize_labels() {
+        let source: SourceFile = SourceFile::new("test.txt", "START: ADD 1\nLOOP:\n  JMP LOOP\n2X: END: HALT\nLAST:");
+        let mut diagnostics: Diagnostics = Diagnostics::new();
+        let (instructions, labels, _): (Vec<Instruction>, Vec<Label>, Vec<Constant>) = Tokenizer::tokenize_instructions(&source, source.content(), source.content(), &mut diagnostics);
+
+        assert_eq!(instructions.len(), 3);
+        assert_eq!(instructions[0].mnemonic(), "ADD");
+        assert_eq!(instructions[0].span(), &Span::new("test.txt", 1, 8, 3));
+        assert_eq!(instructions[1].params(), &vec!["LOOP".to_string()]);
+
+        let names: Vec<(&str, usize)> = labels.iter().map(|label: &Label| (label.name(), label.index())).collect();
+        assert_eq!(names, vec![("START", 0), ("LOOP", 1), ("END", 2), ("LAST", 3)]);
+        assert_eq!(labels[1].span(), &Span::new("test.txt", 2, 1, 4));
+
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics.errors()[0].to_string(), "test.txt:4:1: error[SC0024]: Invalid symbol name '2X'");
     }
 
     #[test]
     fn test_tokenize_variables() {
-        let section: &str = "1 0003\n3 0000";
-        let result: Result<Vec<Variable>, SicompilerError> = Tokenizer::tokenize_variables(section);
+        let source: SourceFile = SourceFile::new("test.txt", "1 0003\n3 0000");
+        let mut diagnostics: Diagnostics = Diagnostics::new();
+        let (result, _): (Vec<Variable>, Vec<Constant>) = Tokenizer::tokenize_variables(&source, source.content(), source.content(), &mut diagnostics);
 
-        assert!(result.is_ok());
-        assert_eq!(result.as_ref().unwrap().len(), 2);
-        assert_eq!(result.as_ref().unwrap()[0].dir(), "1");
-        assert_eq!(result.as_ref().unwrap()[0].name(), "0003");
-        assert_eq!(result.as_ref().unwrap()[1].dir(), "3");
-        assert_eq!(result.as_ref().unwrap()[1].name(), "0000");
+        assert!(diagnostics.is_empty());
+        assert_eq!(result.len(), 2);
+        assert_eq!(result[0].dir(), "1");
+        assert_eq!(result[0].value(), "0003");
+        assert_eq!(result[1].dir(), "3");
+        assert_eq!(result[1].value(), "0000");
+        assert_eq!(result[1].value_span(), &Span::new("test.txt", 2, 3, 4));
 
-        let section: &str = "1=0003\n3 = 0000\n";
-        let result: Result<Vec<Variable>, SicompilerError> = Tokenizer::tokenize_variables(section);
+        let source: SourceFile = SourceFile::new("test.txt", "1\n3 0 0000\n4 0001\n");
+        let mut diagnostics: Diagnostics = Diagnostics::new();
+        let (result, _): (Vec<Variable>, Vec<Constant>) = Tokenizer::tokenize_variables(&source, source.content(), source.content(), &mut diagnostics);
 
-        assert!(result.is_err());
-        assert_eq!(result.unwrap_err().to_string(), "Tokenization error: Invalid variable format, the correct way is <DIR NAME>");
+        assert_eq!(result.len(), 1);
+        assert_eq!(diagnostics.len(), 2);
+        assert_eq!(diagnostics.errors()[0].to_string(), "test.txt:1:1: error[SC0005]: Invalid variable format, the correct way is <DIR VALUE [NAME]>");
+        assert_eq!(diagnostics.errors()[1].to_string(), "test.txt:2:5: error[SC0024]: Invalid symbol name '0000'");
+
+        let source: SourceFile = SourceFile::new("test.txt", "0 0003 count\n1 0000 result\n2 0000 a b\n");
+        let mut diagnostics: Diagnostics = Diagnostics::new();
+        let (result, _): (Vec<Variable>, Vec<Constant>) = Tokenizer::tokenize_variables(&source, source.content(), source.content(), &mut diagnostics);
+
+        assert_eq!(result.len(), 2);
+        assert_eq!(result[0].symbol(), Some("count"));
+        assert_eq!(result[1].symbol_span(), &Span::new("test.txt", 2, 8, 6));
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics.errors()[0].to_string(), "test.txt:3:1: error[SC0005]: Invalid variable format, the correct way is <DIR VALUE [NAME]>");
     }
 
     #[test]
     fn test_tokenize_init() {
-        let section: &str = "1";
-        let init: Result<Init, SicompilerError> = Tokenizer::tokenize_init(section);
+        let source: SourceFile = SourceFile::new("test.txt", "@\n1\n@");
+        let init: Result<Init, SicompilerError> = Tokenizer::tokenize_init(&source, source.content(), &source.content()[1..4]);
 
         assert!(init.is_ok());
-        assert_eq!(init.unwrap().dir(), "1");
+        assert_eq!(init.as_ref().unwrap().dir(), "1");
+        assert_eq!(init.unwrap().span(), &Span::new("test.txt", 2, 1, 1));
 
-        let section: &str = "";
-        let result: Result<Init, SicompilerError> = Tokenizer::tokenize_init(section);
+        let source: SourceFile = SourceFile::new("test.txt", "@@");
+        let result: Result<Init, SicompilerError> = Tokenizer::tokenize_init(&source, source.content(), &source.content()[1..1]);
 
         assert!(result.is_err());
-        assert_eq!(result.unwrap_err().to_string(), "Tokenization error: There is no any Init section.");
+        assert_eq!(result.unwrap_err().to_string(), "test.txt:1:2: error[SC0006]: There is no any Init address");
 
-        let section: &str = " ";
-        let result: Result<Init, SicompilerError> = Tokenizer::tokenize_init(section);
+        let source: SourceFile = SourceFile::new("test.txt", " ");
+        let result: Result<Init, SicompilerError> = Tokenizer::tokenize_init(&source, source.content(), source.content());
     
         assert!(result.is_err());
-        assert_eq!(result.unwrap_err().to_string(), "Tokenization error: There is no any Init address.");
+        assert_eq!(result.unwrap_err().to_string(), "test.txt:1:1: error[SC0006]: There is no any Init address");
         
-        let section: &str = "2 3 5";
-        let result: Result<Init, SicompilerError> = Tokenizer::tokenize_init(section);
+        let source: SourceFile = SourceFile::new("test.txt", "2 3 5");
+        let result: Result<Init, SicompilerError> = Tokenizer::tokenize_init(&source, source.content(), source.content());
     
         assert!(result.is_err());
-        assert_eq!(result.unwrap_err().to_string(), "Tokenization error: There is more than one Init address.");
+        assert_eq!(result.unwrap_err().to_string(), "test.txt:1:3: error[SC0007]: There is more than one Init address");
     
     }
 
     #[test]
     fn test_tokenize_repertoire() {
         let tokenizer: Tokenizer = Tokenizer::new("tests-files/test-input.txt", "tests-files/test-repertoire.rep");
-        let result: Result<HashMap<String, Instruction>, SicompilerError>=  tokenizer.tokenize_repertoire();
+        let result: Result<Repertoire, SicompilerError> = tokenizer.tokenize_repertoire(&mut Diagnostics::new());
 
         assert!(result.is_ok());
         assert_eq!(result.as_ref().unwrap().len(), 2);
         assert_eq!(result.as_ref().unwrap().get("HALT").unwrap().mnemonic(), "HALT");
-        assert_eq!(result.as_ref().unwrap().get("HALT").unwrap().params().len(), 0);
+        assert_eq!(result.as_ref().unwrap().get("HALT").unwrap().operands().len(), 0);
         assert_eq!(result.as_ref().unwrap().get("ADD").unwrap().mnemonic(), "ADD");
-        assert_eq!(result.as_ref().unwrap().get("ADD").unwrap().params().len(), 1);
+        assert_eq!(result.as_ref().unwrap().get("ADD").unwrap().operands(), &vec![Operand::new(OperandKind::Address, 12)]);
+        assert_eq!(result.as_ref().unwrap().microprogram().fetch().len(), 3);
+        assert_eq!(result.as_ref().unwrap().get("ADD").unwrap().microcode().len(), 3);
+        assert_eq!(result.as_ref().unwrap().get("ADD").unwrap().microcode()[2].value(), 0x28200);
         
         let tokenizer: Tokenizer = Tokenizer::new("tests-files/test-input.txt", "tests-files/fails-files/invalid-repertoire.rep");
-        let result: Result<HashMap<String, Instruction>, SicompilerError> = tokenizer.tokenize_repertoire();
+        let result: Result<Repertoire, SicompilerError> = tokenizer.tokenize_repertoire(&mut Diagnostics::new());
     
         assert!(result.is_err());
-        assert_eq!(result.unwrap_err().to_string(), "Tokenization error: Invalid repertoire structure, the file must contain a microprogram section.");
+        assert_eq!(result.unwrap_err().to_string(), "tests-files/fails-files/invalid-repertoire.rep: error[SC0008]: Invalid repertoire structure, the file must contain a microprogram section");
         
         let tokenizer: Tokenizer = Tokenizer::new("tests-files/test-input.txt", "tests-files/fails-files/more-instructions-rep.rep");
-        let result: Result<HashMap<String, Instruction>, SicompilerError> = tokenizer.tokenize_repertoire();
+        let result: Result<Repertoire, SicompilerError> = tokenizer.tokenize_repertoire(&mut Diagnostics::new());
 
         assert!(result.is_err());
-        assert_eq!(result.unwrap_err().to_string(), "Tokenization error: Invalid number of instructions, the max is 32 but get 35");
+        assert_eq!(result.unwrap_err().to_string(), "tests-files/fails-files/more-instructions-rep.rep:38:1: error[SC0009]: Invalid number of instructions, the max is 32 but get 35");
+    }
+
+    #[test]
+    fn test_tokenize_repertoire_structure() {
+        let source: SourceFile = SourceFile::new("test.rep", "$\nCB 4000100\nHALT false 0\n");
+        let result: Result<Repertoire, SicompilerError> = Tokenizer::tokenize_repertoire_source(&source, 28, None, None, &mut Diagnostics::new());
+
+        assert_eq!(result.unwrap_err().to_string(), "test.rep:1:1: error[SC0008]: Invalid repertoire structure, the file must contain a microprogram section");
+
+        let source: SourceFile = SourceFile::new("test.rep", "$\nCB 4000100\n$\nHALT false 0\n$\n");
+        let result: Result<Repertoire, SicompilerError> = Tokenizer::tokenize_repertoire_source(&source, 28, None, None, &mut Diagnostics::new());
+
+        assert_eq!(result.unwrap_err().to_string(), "test.rep:5:1: error[SC0032]: Invalid repertoire structure, the '$' character can only enclose the microprogram section");
+
+        let source: SourceFile = SourceFile::new("test.rep", "$\nCB 4000100\n$\n");
+        let mut diagnostics: Diagnostics = Diagnostics::new();
+        let result: Result<Repertoire, SicompilerError> = Tokenizer::tokenize_repertoire_source(&source, 28, None, None, &mut diagnostics);
+
+        assert!(result.unwrap().is_empty());
+        assert_eq!(diagnostics.errors()[0].to_string(), "test.rep: error[SC0028]: The repertoire does not declare any instruction");
+    }
+
+    #[test]
+    fn test_tokenize_repertoire_lines() {
+        let source: SourceFile = SourceFile::new("test.rep", "$\nCB 4000100\n$\nHALT false 0\nADDJ\nADD yes 8000100\nJMP true 400200\nHALT false 0\n");
+        let mut diagnostics: Diagnostics = Diagnostics::new();
+        let result: Result<Repertoire, SicompilerError> = Tokenizer::tokenize_repertoire_source(&source, 28, None, None, &mut diagnostics);
+
+        assert_eq!(result.unwrap().len(), 3);
+        assert_eq!(diagnostics.len(), 3);
+        assert_eq!(diagnostics.errors()[0].to_string(), "test.rep:5:1: error[SC0029]: Invalid instruction format, the correct way is <MNEMONIC SIGNATURE [WORDS]>");
+        assert_eq!(diagnostics.errors()[1].to_string(), "test.rep:6:5: error[SC0030]: Invalid operand signature 'yes', the correct way is 'true', 'false' or <KIND[:WIDTH],...>");
+        assert_eq!(diagnostics.errors()[2].to_string(), "test.rep:8:1: error[SC0031]: The mnemonic 'HALT' is declared more than once");
+    }
+
+    #[test]
+    fn test_tokenize_repertoire_header() {
+        let source: SourceFile = SourceFile::new("test.rep", "ADDRESS 10\nDATA 24\n$\nCB 4000100\n$\nADD true 8000100\nLDI reg,imm:6 8000100\n");
+        let mut diagnostics: Diagnostics = Diagnostics::new();
+        let repertoire: Repertoire = Tokenizer::tokenize_repertoire_source(&source, 28, None, None, &mut diagnostics).unwrap();
+
+        assert!(diagnostics.is_empty());
+        assert_eq!(repertoire.machine(), &Machine::new(10, 24));
+        assert_eq!(repertoire.get("ADD").unwrap().operands(), &vec![Operand::new(OperandKind::Address, 10)]);
+        assert_eq!(repertoire.get("LDI").unwrap().operands(), &vec![Operand::new(OperandKind::Register, 4), Operand::new(OperandKind::Immediate, 6)]);
+
+        let mut diagnostics: Diagnostics = Diagnostics::new();
+        let repertoire: Repertoire = Tokenizer::tokenize_repertoire_source(&source, 28, Some(8), None, &mut diagnostics).unwrap();
+
+        assert_eq!(repertoire.machine(), &Machine::new(8, 24));
+        assert_eq!(repertoire.get("ADD").unwrap().operands(), &vec![Operand::new(OperandKind::Address, 8)]);
+
+        let source: SourceFile = SourceFile::new("test.rep", "ADDRESS 15\nDATA x\nWORDS 8\n$\nCB 4000100\n$\nHALT false 0\n");
+        let mut diagnostics: Diagnostics = Diagnostics::new();
+        let repertoire: Repertoire = Tokenizer::tokenize_repertoire_source(&source, 28, None, None, &mut diagnostics).unwrap();
+
+        assert_eq!(repertoire.machine(), &Machine::default());
+        assert_eq!(diagnostics.len(), 3);
+        assert_eq!(diagnostics.errors()[0].to_string(), "test.rep:1:9: error[SC0038]: Invalid address width '15', it must be between 1 and 14 bits");
+        assert_eq!(diagnostics.errors()[1].to_string(), "test.rep:2:6: error[SC0038]: Invalid data width 'x', it must be between 1 and 32 bits");
+        assert_eq!(diagnostics.errors()[2].to_string(), "test.rep:3:1: error[SC0037]: Invalid header format, the correct way is <ADDRESS BITS> or <DATA BITS>");
+    }
+
+    #[test]
+    fn test_tokenize_signature() {
+        let source: SourceFile = SourceFile::new("test.rep", "reg:4,imm:8 reg,addr imm:0 mem:4 reg:4,imm:12 false");
+        let tokens: Vec<&str> = source.content().split(' ').collect();
+        let signature = |index: usize| Tokenizer::tokenize_signature(&source, source.content(), tokens[index], &Machine::default());
+
+        assert_eq!(signature(0).unwrap(), vec![Operand::new(OperandKind::Register, 4), Operand::new(OperandKind::Immediate, 8)]);
+        assert_eq!(signature(5).unwrap(), vec![]);
+        assert_eq!(signature(1).unwrap_err().to_string(), "test.rep:1:13: error[SC0033]: The operands 'reg,addr' take 16 bits, but the operand field only has 12");
+        assert_eq!(signature(2).unwrap_err().to_string(), "test.rep:1:22: error[SC0030]: Invalid operand signature 'imm:0', the correct way is 'true', 'false' or <KIND[:WIDTH],...>");
+        assert!(signature(3).is_err());
+        assert!(signature(4).is_err());
+
+        let source: SourceFile = SourceFile::new("test.rep", "imm:4294967295,imm:1");
+        let error: SicompilerError = Tokenizer::tokenize_signature(&source, source.content(), source.content(), &Machine::default()).unwrap_err();
+
+        assert_eq!(error.to_string(), "test.rep:1:1: error[SC0033]: The operands 'imm:4294967295' take 4294967295 bits, but the operand field only has 12");
+    }
+
+    #[test]
+    fn test_tokenize_microprogram() {
+        let source: SourceFile = SourceFile::new("test.rep", "$\nCB 4000100\n  CB 0201100\n$\n");
+        let mut diagnostics: Diagnostics = Diagnostics::new();
+        let microprogram: Microprogram = Tokenizer::tokenize_microprogram(&source, source.content(), source.content().split('$').nth(1).unwrap(), 28, &mut diagnostics);
+
+        assert!(diagnostics.is_empty());
+        assert_eq!(microprogram.fetch().len(), 2);
+        assert_eq!(microprogram.fetch()[0].value(), 0x4000100);
+        assert_eq!(microprogram.fetch()[1].span(), &Span::new("test.rep", 3, 6, 7));
+
+        let source: SourceFile = SourceFile::new("test.rep", "$\nCB 40G0100\nCB 10000000\nJMP 1\n$\n");
+        let mut diagnostics: Diagnostics = Diagnostics::new();
+        let microprogram: Microprogram = Tokenizer::tokenize_microprogram(&source, source.content(), source.content().split('$').nth(1).unwrap(), 28, &mut diagnostics);
+
+        assert!(microprogram.fetch().is_empty());
+        assert_eq!(diagnostics.len(), 3);
+        assert_eq!(diagnostics.errors()[0].to_string(), "test.rep:2:4: error[SC0016]: The control word '40G0100' must be in hex base");
+        assert_eq!(diagnostics.errors()[1].to_string(), "test.rep:3:4: error[SC0017]: The control word '10000000' does not fit in 28 control signals");
+        assert_eq!(diagnostics.errors()[2].to_string(), "test.rep:4:1: error[SC0018]: Invalid microprogram format, the correct way is <CB WORD>");
+    }
+
+    #[test]
+    fn test_tokenize_repertoire_microcode() {
+        let source: SourceFile = SourceFile::new("test.rep", "$\nCB 4000100\n$\nHALT false 0\nADD true 8000100 ZZ\n");
+        let mut diagnostics: Diagnostics = Diagnostics::new();
+        let result: Result<Repertoire, SicompilerError> = Tokenizer::tokenize_repertoire_source(&source, 28, None, None, &mut diagnostics);
+
+        assert!(result.is_ok());
+        assert_eq!(result.as_ref().unwrap().get("HALT").unwrap().microcode()[0].value(), 0);
+        assert_eq!(result.as_ref().unwrap().get("ADD").unwrap().microcode().len(), 1);
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics.errors()[0].to_string(), "test.rep:5:18: error[SC0016]: The control word 'ZZ' must be in hex base");
     }
 
     #[test]
     fn test_tokenize() {
         let tokenizer: Tokenizer = Tokenizer::new("tests-files/test-input.txt", "tests-files/test-repertoire.rep");
-        let result: Result<Program, SicompilerError> = tokenizer.tokenize();
+        let result: Result<Program, SicompilerError> = tokenizer.tokenize(&mut Diagnostics::new());
 
         assert!(result.is_ok());
         assert_eq!(result.as_ref().unwrap().variables().len(), 3);
         assert_eq!(result.as_ref().unwrap().init().dir(), "6");
         assert_eq!(result.as_ref().unwrap().instructions().len(), 2);
+        assert_eq!(result.as_ref().unwrap().instructions()[0].span(), &Span::new("tests-files/test-input.txt", 12, 1, 3));
+        assert_eq!(result.as_ref().unwrap().init().span(), &Span::new("tests-files/test-input.txt", 9, 1, 1));
 
         let tokenizer: Tokenizer = Tokenizer::new("tests-files/fails-files/no-exits-file.txt", "tests-files/test-repertoire.rep");
-        let result: Result<Program, SicompilerError> = tokenizer.tokenize();
+        let result: Result<Program, SicompilerError> = tokenizer.tokenize(&mut Diagnostics::new());
     
         assert!(result.is_err());
-        assert_eq!(result.unwrap_err().to_string(), "I/O error: Can't open tests-files/fails-files/no-exits-file.txt");
+        assert_eq!(result.unwrap_err().to_string(), "error[SC0001]: I/O error: Can't open tests-files/fails-files/no-exits-file.txt");
         
         let tokenizer: Tokenizer = Tokenizer::new("tests-files/fails-files/empty-file.txt", "tests-files/test-repertoire.rep");
-        let result: Result<Program, SicompilerError> = tokenizer.tokenize();
+        let result: Result<Program, SicompilerError> = tokenizer.tokenize(&mut Diagnostics::new());
     
         assert!(result.is_err());
-        assert_eq!(result.unwrap_err().to_string(), "Tokenization error: The file is empty");
+        assert_eq!(result.unwrap_err().to_string(), "tests-files/fails-files/empty-file.txt: error[SC0002]: The file is empty");
         
         let tokenizer: Tokenizer = Tokenizer::new("tests-files/fails-files/invalid-file.txt", "tests-files/test-repertoire.rep");
-        let result: Result<Program, SicompilerError> = tokenizer.tokenize();
+        let result: Result<Program, SicompilerError> = tokenizer.tokenize(&mut Diagnostics::new());
         
         assert!(result.is_err());
-        assert_eq!(result.unwrap_err().to_string(), "Tokenization error: Invalid number of sections, must be 3 but get 1");
+        assert_eq!(result.unwrap_err().to_string(), "tests-files/fails-files/invalid-file.txt: error[SC0003]: Invalid number of sections, must be 3 but get 1");
     
     }
 }
@@ -1,21 +1,36 @@
-use std::collections::HashMap;
-use std::fs::{File, OpenOptions};
-use std::io;
-use std::io::Write;
-
-use crate::models::{program::Program, instruction::Instruction};
-use crate::errors::error::SicompilerError;
+use std::path::Path;
+
+use crate::models::{
+    program::Program,
+    init::Init,
+    span::Span,
+    repertoire::Repertoire,
+    instruction::Instruction,
+    operand::{Operand, OperandKind},
+    variable::Variable,
+    machine::Machine,
+    label::Label,
+    constant::Constant,
+    symbol::{Symbol, SymbolKind, SymbolTable},
+    source::SourceFile,
+    args::OutputFormat
+};
+use crate::errors::{error::SicompilerError, diagnostics::Diagnostics};
+use super::{assembler::Assembler, literal::Literal, expression::Expression, emitter::Emitter, listing::Listing, files, tokenizer::DEFAULT_CONTROL_WIDTH};
 
 /// The `Validator` struct is responsible for validating a sequence of tokens
 /// representing a custom assembly language. It ensures that each instruction
 /// is valid and adheres to the expected format, raising errors if any issues are detected.
 pub struct Validator {
     output_file: String,
-    tokens: Program
+    tokens: Program,
+    format: OutputFormat,
+    control_width: u32,
+    listing_path: Option<String>
 }
 
 impl Validator {
-    /// Check if a parameter is in hexadecimal base
+    /// Check if a parameter is a number, in hexadecimal base or in any other base a `Literal` can be written in
     /// 
     /// ## Arguments
     /// 
@@ -23,194 +38,448 @@ impl Validator {
     /// 
     /// ## Returns
     /// 
-    /// - True if is in hexadecimal base
-    /// - False if is not in hexadecimal base
+    /// - True if every parameter is a well written number
+    /// - False if any of them is not
     ///
-    fn is_hex(params: &Vec<String>) -> bool {
-        params.iter().all(|param: &String| {
-            param.chars()
-                .next()
-                .map_or(false, |first_char: char| first_char.is_ascii_hexdigit())
-        })
+    fn is_number(params: &[String]) -> bool {
+        params.iter().all(|param: &String| Literal::is_valid(param))
+    }
+
+    /// Returns the path of the control ROM image, the output path with `-microcode` after its name,
+    /// e.g. `out-microcode.txt` for `out.txt`, or `out-microcode.txt` if the output is the standard output.
+    fn microcode_path(&self) -> String {
+        let output_file: &str = if self.output_file == files::STDIO_PATH { "out.txt" } else { &self.output_file };
+        let path: &Path = Path::new(output_file);
+        let stem: String = path.file_stem().unwrap_or_default().to_string_lossy().to_string();
+        let name: String = match path.extension() {
+            Some(extension) => format!("{}-microcode.{}", stem, extension.to_string_lossy()),
+            None => format!("{}-microcode", stem)
+        };
+
+        path.with_file_name(name).to_string_lossy().to_string()
     }
 
-    /// Writes the tokenized information to an output file.
+    /// Renders every output of the program before any of them is written: the program, as text or as
+    /// the assembled memory image, the control ROM image with the microcode of the repertoire for
+    /// the Logisim and Digital formats, and the listing if one was requested.
     /// 
     /// ## Arguments
     /// 
-    /// - `&self` - Reference to the `Tokenizer` instance.
+    /// - `&self` - Reference to the `Validator` instance.
+    /// - `repertoire` - The valid instructions, which give the opcodes of the memory image.
+    /// - `source` - The source of the program, which the listing shows next to each word.
     /// 
     /// ## Returns
     /// 
-    /// - `Result<(), SicompilerError>` - Result indicating success or an `Error` if any issues occur during file writing.
+    /// - `Result<Vec<(String, Vec<u8>)>, SicompilerError>` - The path and the content of each output, or an `Error` if one can not be rendered.
     ///     
-    fn write_file(&self) -> Result<(), SicompilerError> {
-        let mut file: File = OpenOptions::new()
-            .create(true)
-            .write(true)
-            .open(&self.output_file)
-            .map_err(|e: io::Error| 
-                SicompilerError::Io(io::Error::new(e.kind(), format!("Can't open {}", self.output_file)))
-            )?;
-
-        for variable in self.tokens.variables() {
-            file.write_all(format!("{} {}\n", variable.dir(), variable.name()).as_bytes())?;
-        }
+    fn render(&self, repertoire: &Repertoire, source: Option<&SourceFile>) -> Result<Vec<(String, Vec<u8>)>, SicompilerError> {
+        let mut outputs: Vec<(String, Vec<u8>)> = vec![(self.output_file.clone(), Emitter::emit(&self.tokens, repertoire, self.format)?)];
 
-        writeln!(file, "@")?;
-        writeln!(file, "{}", self.tokens.init().dir())?;
-        writeln!(file, "@")?;
+        if let Some(microcode) = Emitter::emit_microcode(repertoire, self.format, self.control_width) {
+            outputs.push((self.microcode_path(), microcode));
+        }
 
-        for instruction in self.tokens.instructions() {
-            file.write_all(format!("{} {}\n", instruction.mnemonic(), instruction.params().join(" ")).as_bytes())?;
+        if let Some(path) = &self.listing_path {
+            let listing: String = source.map_or_else(|| Ok(String::new()), |source: &SourceFile| Listing::render(&self.tokens, repertoire, source))?;
+            outputs.push((path.clone(), listing.into_bytes()));
         }
 
-        Ok(())
+        Ok(outputs)
     }
 
-    /// Validates the tokenized program to ensure it contains both instructions and variables sections.
+    /// Writes every output of the program, or to the standard output the ones whose path is `-`.
+    /// 
+    /// All the outputs are rendered and checked first, so nothing is written if one of them fails
+    /// or would overwrite the program or the repertoire. They are then written together with `files::write_all`,
+    /// so none of them is replaced unless all of them can be written.
     /// 
     /// ## Arguments
     /// 
-    /// - `&self` - Reference to the `Tokenizer` instance.
+    /// - `&self` - Reference to the `Validator` instance.
+    /// - `repertoire` - The valid instructions, which give the opcodes of the memory image.
+    /// - `source` - The source of the program, which the listing shows next to each word.
     /// 
     /// ## Returns
     /// 
-    /// - `Result<(), SicompilerError>` - Result indicating success or an `Error` if there is no instructions or variables section.
-    /// 
-    fn validate_program(&self) -> Result<(), SicompilerError> {
-        if self.tokens.variables().is_empty() || self.tokens.instructions().is_empty() {
-            return Err(SicompilerError::ValidationError("There is not any instructions or variables section".to_string()));
+    /// - `Result<(), SicompilerError>` - Result indicating success or an `Error` if any issues occur during file writing.
+    ///     
+    fn write_file(&self, repertoire: &Repertoire, source: Option<&SourceFile>) -> Result<(), SicompilerError> {
+        let outputs: Vec<(String, Vec<u8>)> = self.render(repertoire, source)?;
+        let inputs: [&str; 2] = [self.tokens.span().file(), repertoire.path()];
+
+        for (path, _) in &outputs {
+            files::check_overwrite(path, &inputs)?;
         }
 
-        Ok(())
+        files::write_all(&outputs)
     }
 
-    /// Validates the tokenized variables to ensure both directory and name are in hexadecimal format.
+    /// Validates the tokenized program to ensure it contains both instructions and variables sections.
     /// 
     /// ## Arguments
     /// 
-    /// - `&self` - Reference to the `Tokenizer` instance.
+    /// - `program` - The tokenized program.
+    /// - `diagnostics` - The collector where the error is reported if there is no instructions or variables section.
     /// 
-    /// ## Returns
+    fn validate_program(program: &Program, diagnostics: &mut Diagnostics) {
+        if program.variables().is_empty() || program.instructions().is_empty() {
+            diagnostics.push(SicompilerError::EmptySection { span: program.span().clone() });
+        }
+    }
+
+    /// Validates the tokenized variables to ensure the directory is a number inside the memory and the
+    /// value a number or an expression inside the data words of the machine.
+    /// 
+    /// ## Arguments
     /// 
-    /// - `Result<(), SicompilerError>` - Result indicating success or an `Error` if any variable has a non-hexadecimal directory or name.
+    /// - `program` - The tokenized program.
+    /// - `machine` - The machine the program runs on.
+    /// - `diagnostics` - The collector where every variable with a non-hexadecimal or out of range directory or value is reported.
     /// 
-    fn validate_variables(&self) -> Result<(), SicompilerError> {
-        for variable in self.tokens.variables() {
-            let var: Vec<String> = vec![variable.dir().to_string(), variable.name().to_string()];
+    fn validate_variables(program: &Program, machine: &Machine, diagnostics: &mut Diagnostics) {
+        let symbols: SymbolTable = SymbolTable::from_program(program);
+
+        for variable in program.variables() {
+            let var: Vec<String> = vec![variable.dir().to_string(), variable.value().to_string()];
+            let is_value: bool = Validator::is_number(&var[1..]) || Expression::is_expression(variable.value()) || SymbolTable::is_valid_name(variable.value());
             
-            if !Validator::is_hex(&var) {
-                return Err(SicompilerError::ValidationError(
-                    format!("The varibale dir and name must be in hex base '{} {}'", variable.dir(), variable.name())
-                ));
-            } 
+            if !Validator::is_number(&var[..1]) || !is_value {
+                let span: &Span = if Validator::is_number(&var[..1]) { variable.value_span() } else { variable.dir_span() };
+
+                diagnostics.push(SicompilerError::NonHexVariable {
+                    dir: variable.dir().to_string(),
+                    value: variable.value().to_string(),
+                    span: span.clone()
+                });
+
+                continue;
+            }
+
+            if let Err(err) = Assembler::parse_address(variable.dir(), machine, variable.dir_span()) {
+                diagnostics.push(err);
+            }
+
+            let dir: u64 = Literal::parse(variable.dir()).unwrap_or_default();
+
+            if let Err(err) = Assembler::parse_data(variable.value(), &symbols, dir, machine, variable.value_span()) {
+                diagnostics.push(err);
+            }
         }
+    }
 
-        Ok(())
+    /// Validates the tokenized initialization directory to ensure it is in hexadecimal format and inside the memory.
+    /// 
+    /// An empty directory means the init section could not be tokenized, which the `Tokenizer` already reported.
+    /// 
+    /// ## Arguments
+    /// 
+    /// - `program` - The tokenized program.
+    /// - `machine` - The machine the program runs on.
+    /// - `diagnostics` - The collector where the error is reported if the initialization directory is not in hexadecimal format or out of range.
+    /// 
+    fn validate_init(program: &Program, machine: &Machine, diagnostics: &mut Diagnostics) {
+        let init: &Init = program.init();
+
+        if init.dir().is_empty() { return }
+
+        if !Validator::is_number(&[init.dir().to_string()]) {
+            diagnostics.push(SicompilerError::NonHexInit { dir: init.dir().to_string(), span: init.span().clone() });
+        } else if let Err(err) = Assembler::parse_address(init.dir(), machine, init.span()) {
+            diagnostics.push(err);
+        }
     }
 
-    /// Validates the tokenized initialization directory to ensure it is in hexadecimal format.
+    /// Validates the placement of the instructions to ensure the last one still fits in the memory of the machine.
+    /// 
+    /// An init directory out of the memory is left to `validate_init`, so only the first instruction past the
+    /// last cell is reported, as `Assembler::assemble` does.
     /// 
     /// ## Arguments
     /// 
-    /// - `&self` - Reference to the `Tokenizer` instance.
+    /// - `program` - The tokenized program.
+    /// - `machine` - The machine the program runs on.
+    /// - `diagnostics` - The collector where the first instruction out of the memory is reported.
     /// 
-    /// ## Returns
+    fn validate_placement(program: &Program, machine: &Machine, diagnostics: &mut Diagnostics) {
+        let Some(init) = Literal::parse(program.init().dir()) else { return };
+
+        if init > machine.address_max() { return }
+
+        let outside: Option<(u64, &Instruction)> = (init..).zip(program.instructions())
+            .find(|(address, _): &(u64, &Instruction)| *address > machine.address_max());
+
+        if let Some((address, instruction)) = outside {
+            diagnostics.push(SicompilerError::AddressOutOfRange {
+                address: format!("{:X}", address),
+                max: machine.address_max(),
+                span: instruction.span().clone()
+            });
+        }
+    }
+
+    /// Validates the layout of the program in memory to ensure no variable or instruction is placed at an address another one takes.
+    /// 
+    /// ## Arguments
     /// 
-    /// - `Result<(), SicompilerError>` - Result indicating success or an `Error` if the initialization directory is not in hexadecimal format.
+    /// - `program` - The tokenized program.
+    /// - `machine` - The machine the program runs on.
+    /// - `diagnostics` - The collector where every overlapping variable or instruction is reported.
     /// 
-    fn validate_init(&self) -> Result<(), SicompilerError> {
-        if !Validator::is_hex(&vec![self.tokens.init().dir().to_string()]) {
-            return Err(SicompilerError::ValidationError(
-                format!("The init dir must be in hex base '{}'", self.tokens.init().dir())
-            ));
+    fn validate_layout(program: &Program, machine: &Machine, diagnostics: &mut Diagnostics) {
+        for err in Assembler::overlaps(program, machine) {
+            diagnostics.push(err);
         }
+    }
+
+    /// Validates the names of the variables, the labels and the constants to ensure none of them is defined twice.
+    /// 
+    /// ## Arguments
+    /// 
+    /// - `program` - The tokenized program.
+    /// - `diagnostics` - The collector where every repeated name is reported.
+    /// 
+    fn validate_symbols(program: &Program, diagnostics: &mut Diagnostics) {
+        let mut symbols: SymbolTable = SymbolTable::new();
 
-        Ok(())
+        let mut definitions: Vec<Symbol> = program.variables().iter()
+            .filter_map(|variable: &Variable| variable.symbol().map(|name: &str| {
+                Symbol::new(name, SymbolKind::Variable, 0, variable.symbol_span().clone())
+            }))
+            .collect();
+
+        definitions.extend(program.labels().iter().map(|label: &Label| {
+            Symbol::new(label.name(), SymbolKind::Label, label.index() as u64, label.span().clone())
+        }));
+
+        definitions.extend(program.constants().iter().map(|constant: &Constant| {
+            Symbol::new(constant.name(), SymbolKind::Constant, 0, constant.span().clone())
+        }));
+
+        for symbol in definitions {
+            let name: String = symbol.name().to_string();
+            let span: Span = symbol.span().clone();
+
+            if let Err(first) = symbols.insert(symbol) {
+                diagnostics.push(SicompilerError::DuplicateSymbol { name, first: first.span().clone(), span });
+            }
+        }
     }
 
-    /// Validates the tokenized instructions to ensure they are valid and have the correct parameters.
+    /// Validates the constants to ensure their values can be evaluated, naming only the symbols defined before them.
     /// 
     /// ## Arguments
     /// 
-    /// - `&self` - Reference to the `Tokenizer` instance.
+    /// - `program` - The tokenized program.
+    /// - `diagnostics` - The collector where every constant that can not be evaluated is reported.
     /// 
-    /// ## Returns
+    fn validate_constants(program: &Program, diagnostics: &mut Diagnostics) {
+        let (_, errors): (SymbolTable, Vec<SicompilerError>) = SymbolTable::from_program_checked(program);
+
+        for err in errors {
+            diagnostics.push(err);
+        }
+    }
+
+    /// Validates the tokenized instructions to ensure they are valid and have the correct parameters.
     /// 
-    /// - `Result<(), SicompilerError>` - Result indicating success or an `Error` if any instruction is invalid or has incorrect parameters.
+    /// Only the first problem of each instruction is reported.
     /// 
-    fn validate_instructions(&self, repertoire: &HashMap<String, Instruction>) -> Result<(), SicompilerError> {
-        for instruction in self.tokens.instructions() {
-            if !repertoire.contains_key(instruction.mnemonic()) {
-                return Err(SicompilerError::ValidationError(
-                    format!("Invalid instruction, '{}' does not appear in the repertoire", instruction.mnemonic())
-                ));
-            }
-            
-            let rep_instruction: &Instruction = match repertoire.get(instruction.mnemonic()) {
-                Some(instruction) => instruction,
-                None => {
-                    return Err(SicompilerError::ValidationError(
-                        format!("The instruction '{}' is not defined in the repertoire", instruction.mnemonic())
-                    ));
-                }
+    /// ## Arguments
+    /// 
+    /// - `program` - The tokenized program.
+    /// - `repertoire` - The valid instructions.
+    /// - `diagnostics` - The collector where every invalid instruction is reported.
+    /// 
+    fn validate_instructions(program: &Program, repertoire: &Repertoire, diagnostics: &mut Diagnostics) {
+        let symbols: SymbolTable = SymbolTable::from_program(program);
+        let init: u64 = Literal::parse(program.init().dir()).unwrap_or_default();
+
+        for (address, instruction) in (init..).zip(program.instructions()) {
+            let Some(rep_instruction) = repertoire.get(instruction.mnemonic()) else {
+                diagnostics.push(SicompilerError::UnknownMnemonic {
+                    mnemonic: instruction.mnemonic().to_string(),
+                    span: instruction.span().clone()
+                });
+                continue;
             };
             
-            if instruction.flag() != rep_instruction.flag() {                
-                return Err(SicompilerError::ValidationError(
-                    format!("The instruction '{}' must have some parameters", instruction.mnemonic())
-                ));
-            }
-
             let params: &Vec<String> = instruction.params();
-            let rep_params: &Vec<String> = rep_instruction.params();
+            let operands: &Vec<Operand> = rep_instruction.operands();
             
-            if params.len() != rep_params.len() {
-                return Err(SicompilerError::ValidationError(
-                    format!(
-                        "Invalid number of parameters in '{}', only has {} but get {}", 
-                        instruction.mnemonic(), 
-                        rep_params.len(), 
-                        instruction.params().len()
-                    )
-                ));
+            if params.len() != operands.len() {
+                diagnostics.push(SicompilerError::WrongOperandCount {
+                    mnemonic: instruction.mnemonic().to_string(),
+                    expected: operands.len(),
+                    found: params.len(),
+                    span: instruction.param_span(operands.len()).clone(),
+                    entry: rep_instruction.span().clone()
+                });
+                continue;
             } 
 
-            if instruction.flag() && !Validator::is_hex(&params) {
-                return Err(SicompilerError::ValidationError(
-                    format!("Invalid parameters in '{}', the parameters must be in hex base", instruction.mnemonic())
-                ));
+            for (index, (param, operand)) in params.iter().zip(operands).enumerate() {
+                if let Err(err) = Validator::validate_operand(instruction, rep_instruction, index, param, operand, &symbols, address) {
+                    diagnostics.push(err);
+                    break;
+                }
             }
         }
+    }
+
+    /// Validates an operand of an instruction against the kind and the width its repertoire entry declares.
+    /// 
+    /// ## Arguments
+    /// 
+    /// - `instruction` - The instruction of the program.
+    /// - `entry` - The repertoire instruction.
+    /// - `index` - The position of the operand.
+    /// - `param` - The operand as written in the program.
+    /// - `operand` - The signature of the operand.
+    /// - `symbols` - The labels and variables the operand can name.
+    /// - `address` - The address of the instruction, the value of `$`.
+    /// 
+    /// ## Returns
+    /// 
+    /// - `Result<(), SicompilerError>` - An `Error` if the operand is not valid.
+    /// 
+    fn validate_operand(instruction: &Instruction, entry: &Instruction, index: usize, param: &str, operand: &Operand, symbols: &SymbolTable, address: u64) -> Result<(), SicompilerError> {
+        let span: Span = instruction.param_span(index).clone();
+        let mnemonic: String = instruction.mnemonic().to_string();
+        let is_register: bool = Assembler::parse_register(param).is_some();
+
+        let wrong_kind = || SicompilerError::WrongOperandKind {
+            mnemonic: mnemonic.clone(),
+            operand: param.to_string(),
+            expected: operand.kind().name().to_string(),
+            span: span.clone(),
+            entry: entry.span().clone()
+        };
+
+        match operand.kind() {
+            OperandKind::Register if !is_register => return Err(wrong_kind()),
+            OperandKind::Register => {},
+            //* Operands naming a label or a variable are valid, any other must be a number or an expression
+            _ if symbols.get(param).is_some() => {},
+            _ if is_register => return Err(wrong_kind()),
+            _ if SymbolTable::is_valid_name(param) => return Err(SicompilerError::UndefinedSymbol { name: param.to_string(), span }),
+            _ if Expression::is_expression(param) => {
+                Expression::evaluate(param, symbols, address, &span)?;
+            },
+            _ if !Literal::is_valid(param) => {
+                return Err(SicompilerError::NonHexOperand { mnemonic, operand: param.to_string(), span, entry: entry.span().clone() });
+            },
+            _ => {}
+        }
 
-        Ok(())
+        match Assembler::operand_value(param, operand.kind(), symbols, address) {
+            Some(value) if value <= operand.max() => Ok(()),
+            _ => Err(SicompilerError::OperandOutOfRange {
+                mnemonic,
+                operand: param.to_string(),
+                width: operand.width(),
+                span,
+                entry: entry.span().clone()
+            })
+        }
     }
     
     /// Creates a new `Validator` instance with the specified tokens and output file.
+    /// A program that is only checked, without writing it, needs no `Validator`, see `check_program`.
     pub fn new(tokens: Program, output_file: &str) -> Validator {
-        Validator { tokens, output_file: output_file.to_string() }
+        Validator { tokens, output_file: output_file.to_string(), format: OutputFormat::default(), control_width: DEFAULT_CONTROL_WIDTH, listing_path: None }
+    }
+
+    /// Sets the format the program is written in.
+    /// 
+    /// ## Arguments
+    /// 
+    /// - `format` - The output format, the text format by default.
+    /// 
+    pub fn set_format(&mut self, format: OutputFormat) {
+        self.format = format;
+    }
+
+    /// Sets the number of control signals of the control ROM image written with the Logisim and Digital formats.
+    /// 
+    /// ## Arguments
+    /// 
+    /// - `control_width` - The number of control signals, `DEFAULT_CONTROL_WIDTH` by default.
+    /// 
+    pub fn set_control_width(&mut self, control_width: u32) {
+        self.control_width = control_width;
     }
 
+    /// Sets the path the listing of the program is written to.
+    /// 
+    /// ## Arguments
+    /// 
+    /// - `listing_path` - The path of the listing, or `None` to write no listing.
+    /// 
+    pub fn set_listing_path(&mut self, listing_path: Option<String>) {
+        self.listing_path = listing_path;
+    }
+
+    pub fn tokens(&self) -> &Program { &self.tokens }
+
+    /// Consumes the validator, returning the tokenized program.
+    pub fn into_tokens(self) -> Program { self.tokens }
+
     /// Validates the tokenized program, variables, initialization directory, and instructions,
-    /// and writes the validated information to an output file.
+    /// without writing the output file.
     /// 
     /// ## Arguments
     /// 
-    /// - `&self` - Reference to the `Tokenizer` instance.
+    /// - `&self` - Reference to the `Validator` instance.
+    /// - `repertoire` - The valid instructions.
+    /// - `diagnostics` - The collector where every problem is reported.
     /// 
-    /// ## Returns
+    pub fn check(&self, repertoire: &Repertoire, diagnostics: &mut Diagnostics) {
+        Validator::check_program(&self.tokens, repertoire, diagnostics);
+    }
+
+    /// Validates a program borrowed from the caller, without building a `Validator` or writing any file.
+    /// 
+    /// ## Arguments
+    /// 
+    /// - `program` - The tokenized program, whose spans point to the file it was read from.
+    /// - `repertoire` - The valid instructions.
+    /// - `diagnostics` - The collector where every problem is reported.
+    /// 
+    pub fn check_program(program: &Program, repertoire: &Repertoire, diagnostics: &mut Diagnostics) {
+        Validator::validate_program(program, diagnostics);
+        Validator::validate_variables(program, repertoire.machine(), diagnostics);
+        Validator::validate_init(program, repertoire.machine(), diagnostics);
+        Validator::validate_placement(program, repertoire.machine(), diagnostics);
+        Validator::validate_layout(program, repertoire.machine(), diagnostics);
+        Validator::validate_symbols(program, diagnostics);
+        Validator::validate_constants(program, diagnostics);
+        Validator::validate_instructions(program, repertoire, diagnostics);
+    }
+
+    /// Validates the tokenized program, variables, initialization directory, and instructions,
+    /// and writes the validated information to an output file.
     /// 
-    /// - `Result<(), SicompilerError>` - Result indicating success or an `Error` if any validation step fails.
+    /// Every validation step runs even if a previous one failed. The output file is only written
+    /// when `diagnostics` is still empty, so problems found by the `Tokenizer` also prevent it.
     /// 
-    pub fn validate(&self, repertoire: &HashMap<String, Instruction>) -> Result<(), SicompilerError> {
-        self.validate_program()?;
-        self.validate_variables()?;
-        self.validate_init()?;
-        self.validate_instructions(repertoire)?;
-        self.write_file()?;
-        
-        Ok(())
+    /// ## Arguments
+    /// 
+    /// - `&self` - Reference to the `Tokenizer` instance.
+    /// - `repertoire` - The valid instructions.
+    /// - `diagnostics` - The collector where every problem is reported.
+    /// 
+    pub fn validate(&self, repertoire: &Repertoire, diagnostics: &mut Diagnostics) {
+        self.check(repertoire, diagnostics);
+
+        if diagnostics.is_empty() {
+            let source: Option<&SourceFile> = diagnostics.source(self.tokens.span().file());
+            let result: Result<(), SicompilerError> = self.write_file(repertoire, source);
+
+            if let Err(err) = result {
+                diagnostics.push(err);
+            }
+        }
     }
 }
 
@@ -221,17 +490,23 @@ mod tests {
         variable::Variable,
         instruction::Instruction, 
         program::Program, 
+        label::Label,
+        microprogram::Microprogram,
     };
+    use crate::structs::fixtures::entry;
 
     use super::*;
 
     #[test]
-    fn test_is_hex() {
+    fn test_is_number() {
         let good_params: Vec<String> = vec!["ABCD".to_string(), "1234".to_string()];
         let bad_params: Vec<String> = vec!["GGGGG".to_string(), "845648".to_string()];
         
-        assert!(Validator::is_hex(&good_params));
-        assert!(!Validator::is_hex(&bad_params));
+        assert!(Validator::is_number(&good_params));
+        assert!(!Validator::is_number(&bad_params));
+        assert!(!Validator::is_number(&["1ZZZ".to_string()]));
+        assert!(!Validator::is_number(&["".to_string()]));
+        assert!(Validator::is_number(&["0x1F".to_string(), "0b1010".to_string(), "d'25".to_string(), "'A'".to_string()]));
     }
 
     #[test]
@@ -242,9 +517,10 @@ mod tests {
             vec![Instruction::new("ADD", vec!["1"])]
         );
 
+        let repertoire: Repertoire = Repertoire::new(Microprogram::default(), vec![entry("ADD")]);
         let validator: Validator = Validator::new(tokens, "tests-files/test.txt");
 
-        assert!(validator.write_file().is_ok());
+        assert!(validator.write_file(&repertoire, None).is_ok());
 
         let content: String = std::fs::read_to_string("tests-files/test.txt").unwrap();
         let expected_content: &str = "A B
@@ -257,6 +533,44 @@ ADD 1
         assert_eq!(content, expected_content);
     }
 
+    #[test]
+    fn test_write_file_overwrite_input() {
+        std::fs::write("tests-files/test-overwrite.txt", "0 0003\n@\n1\n@\nADD 0\n").unwrap();
+
+        let mut tokens: Program = Program::new(vec![Variable::new("0", "0003")], Init::new("1"), vec![Instruction::new("ADD", vec!["0"])]);
+        tokens.set_span(Span::file_only("tests-files/test-overwrite.txt"));
+
+        let repertoire: Repertoire = Repertoire::new(Microprogram::default(), vec![entry("ADD")]);
+        let mut validator: Validator = Validator::new(tokens, "tests-files/test-overwrite-out.txt");
+        validator.set_listing_path(Some("tests-files/test-overwrite.txt".to_string()));
+
+        let result: Result<(), SicompilerError> = validator.write_file(&repertoire, None);
+
+        assert_eq!(result.unwrap_err().to_string(), "tests-files/test-overwrite.txt: error[SC0027]: The output path 'tests-files/test-overwrite.txt' is an input file");
+        assert_eq!(std::fs::read_to_string("tests-files/test-overwrite.txt").unwrap(), "0 0003\n@\n1\n@\nADD 0\n");
+        assert!(!Path::new("tests-files/test-overwrite-out.txt").exists());
+
+        let tokens: Program = Program::new(vec![Variable::new("0", "0003")], Init::new("1"), vec![]);
+        let mut repertoire: Repertoire = Repertoire::new(Microprogram::default(), vec![]);
+        repertoire.set_path("tests-files/test-repertoire.rep");
+        let mut validator: Validator = Validator::new(tokens, "tests-files/test-overwrite-out.txt");
+        validator.set_listing_path(Some("tests-files/./test-repertoire.rep".to_string()));
+
+        let result: Result<(), SicompilerError> = validator.write_file(&repertoire, None);
+
+        assert!(result.unwrap_err().to_string().starts_with("tests-files/test-repertoire.rep: error[SC0027]"));
+        assert!(!Path::new("tests-files/test-overwrite-out.txt").exists());
+    }
+
+    #[test]
+    fn test_microcode_path() {
+        let validator: Validator = Validator::new(Program::new(vec![], Init::new("0"), vec![]), "tests-files/out.hex");
+        assert_eq!(validator.microcode_path(), "tests-files/out-microcode.hex");
+
+        let validator: Validator = Validator::new(Program::new(vec![], Init::new("0"), vec![]), "rom");
+        assert_eq!(validator.microcode_path(), "rom-microcode");
+    }
+
     #[test]
     fn test_validate_program() {
         let tokens: Program = Program::new(
@@ -265,9 +579,11 @@ ADD 1
             vec![Instruction::new("ADD", vec!["1"])]
         );
 
-        let validator: Validator = Validator::new(tokens, "");
 
-        assert!(validator.validate_program().is_ok());
+        let mut diagnostics: Diagnostics = Diagnostics::new();
+        Validator::validate_program(&tokens, &mut diagnostics);
+
+        assert!(diagnostics.is_empty());
 
         let tokens: Program = Program::new(
             vec![Variable::new("A", "B")],
@@ -275,11 +591,11 @@ ADD 1
             vec![]
         );
 
-        let validator: Validator = Validator::new(tokens, "");
-        let result: Result<(), SicompilerError> = validator.validate_program();
+        let mut diagnostics: Diagnostics = Diagnostics::new();
+        Validator::validate_program(&tokens, &mut diagnostics);
 
-        assert!(result.is_err());
-        assert_eq!(result.unwrap_err().to_string(), "Validation error: There is not any instructions or variables section");
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics.errors()[0].to_string(), "error[SC0010]: There is not any instructions or variables section");
     }
 
     #[test]
@@ -290,9 +606,11 @@ ADD 1
             vec![Instruction::new("ADD", vec!["1"])]
         );
 
-        let validator: Validator = Validator::new(tokens, "");
 
-        assert!(validator.validate_variables().is_ok());
+        let mut diagnostics: Diagnostics = Diagnostics::new();
+        Validator::validate_variables(&tokens, &Machine::default(), &mut diagnostics);
+
+        assert!(diagnostics.is_empty());
 
         let tokens: Program = Program::new(
             vec![Variable::new("HHHHH", "UUUUU")],
@@ -300,11 +618,30 @@ ADD 1
             vec![Instruction::new("ADD", vec!["1"])]
         );
 
-        let validator: Validator = Validator::new(tokens, "");
-        let result: Result<(), SicompilerError> = validator.validate_variables();
+        let mut diagnostics: Diagnostics = Diagnostics::new();
+        Validator::validate_variables(&tokens, &Machine::default(), &mut diagnostics);
+
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics.errors()[0].to_string(), "error[SC0011]: The variable dir and value must be a number (hex, 0x…, 0b…, 0o…, d'…, 'c') 'HHHHH UUUUU'");
+
+        let tokens: Program = Program::new(
+            vec![Variable::new("1000", "FFFF"), Variable::new("0", "10000"), Variable::new("3", "1ZZZ")],
+            Init::new("2"),
+            vec![Instruction::new("ADD", vec!["1"])]
+        );
 
-        assert!(result.is_err());
-        assert_eq!(result.unwrap_err().to_string(), "Validation error: The varibale dir and name must be in hex base 'HHHHH UUUUU'");
+        let mut diagnostics: Diagnostics = Diagnostics::new();
+        Validator::validate_variables(&tokens, &Machine::default(), &mut diagnostics);
+
+        assert_eq!(diagnostics.len(), 3);
+        assert_eq!(diagnostics.errors()[0].code(), "SC0019");
+        assert_eq!(diagnostics.errors()[1].to_string(), "error[SC0036]: The value '10000' does not fit in 16 data bits, the max is 'FFFF'");
+        assert_eq!(diagnostics.errors()[2].code(), "SC0011");
+
+        let mut diagnostics: Diagnostics = Diagnostics::new();
+        Validator::validate_variables(&tokens, &Machine::new(13, 20), &mut diagnostics);
+
+        assert_eq!(diagnostics.len(), 1);
     }
 
     #[test]
@@ -315,9 +652,11 @@ ADD 1
             vec![Instruction::new("ADD", vec!["1"])]
         );
 
-        let validator: Validator = Validator::new(tokens, "");
 
-        assert!(validator.validate_init().is_ok());
+        let mut diagnostics: Diagnostics = Diagnostics::new();
+        Validator::validate_init(&tokens, &Machine::default(), &mut diagnostics);
+
+        assert!(diagnostics.is_empty());
 
         let tokens: Program = Program::new(
             vec![Variable::new("A", "B")],
@@ -325,18 +664,30 @@ ADD 1
             vec![Instruction::new("ADD", vec!["1"])]
         );
 
-        let validator: Validator = Validator::new(tokens, "");
-        let result: Result<(), SicompilerError> = validator.validate_init();
+        let mut diagnostics: Diagnostics = Diagnostics::new();
+        Validator::validate_init(&tokens, &Machine::default(), &mut diagnostics);
 
-        assert!(result.is_err());
-        assert_eq!(result.unwrap_err().to_string(), "Validation error: The init dir must be in hex base 'GGGGG'");
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics.errors()[0].to_string(), "error[SC0012]: The init dir must be a number (hex, 0x…, 0b…, 0o…, d'…, 'c') 'GGGGG'");
+
+        let tokens: Program = Program::new(
+            vec![Variable::new("A", "B")],
+            Init::new("1000"),
+            vec![Instruction::new("ADD", vec!["1"])]
+        );
+
+        let mut diagnostics: Diagnostics = Diagnostics::new();
+        Validator::validate_init(&tokens, &Machine::default(), &mut diagnostics);
+
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics.errors()[0].code(), "SC0019");
     }
 
     #[test]
     fn test_validate_instructions() {
-        let repertoire: HashMap<String, Instruction> = HashMap::from([
-            ("ADD".to_string(), Instruction::new("ADD", vec!["0x123"])),
-            ("HALT".to_string(), Instruction::new("HALT", vec![]))
+        let repertoire: Repertoire = Repertoire::new(Microprogram::default(), vec![
+            entry("ADD"),
+            Instruction::new("HALT", vec![])
         ]);
         
         let tokens: Program = Program::new(
@@ -345,9 +696,11 @@ ADD 1
             vec![Instruction::new("ADD", vec!["1"])]
         );
 
-        let validator: Validator = Validator::new(tokens, "");
 
-        assert!(validator.validate_instructions(&repertoire).is_ok());
+        let mut diagnostics: Diagnostics = Diagnostics::new();
+        Validator::validate_instructions(&tokens, &repertoire, &mut diagnostics);
+
+        assert!(diagnostics.is_empty());
 
         let tokens: Program = Program::new(
             vec![Variable::new("A", "B")],
@@ -355,11 +708,22 @@ ADD 1
             vec![Instruction::new("SUB", vec!["1"])]
         );
 
-        let validator: Validator = Validator::new(tokens, "");
-        let result: Result<(), SicompilerError> = validator.validate_instructions(&repertoire);
+        let mut diagnostics: Diagnostics = Diagnostics::new();
+        Validator::validate_instructions(&tokens, &repertoire, &mut diagnostics);
+
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics.errors()[0].to_string(), "error[SC0013]: Invalid instruction, 'SUB' does not appear in the repertoire");
+        assert!(matches!(&diagnostics.errors()[0], SicompilerError::UnknownMnemonic { mnemonic, .. } if mnemonic == "SUB"));
+
+        let mut instruction: Instruction = Instruction::new("SUB", vec!["1"]);
+        instruction.set_span(Span::new("program.txt", 12, 3, 3));
 
-        assert!(result.is_err());
-        assert_eq!(result.unwrap_err().to_string(), "Validation error: Invalid instruction, 'SUB' does not appear in the repertoire");
+        let tokens: Program = Program::new(vec![Variable::new("A", "B")], Init::new("2"), vec![instruction]);
+
+        let mut diagnostics: Diagnostics = Diagnostics::new();
+        Validator::validate_instructions(&tokens, &repertoire, &mut diagnostics);
+
+        assert_eq!(diagnostics.errors()[0].to_string(), "program.txt:12:3: error[SC0013]: Invalid instruction, 'SUB' does not appear in the repertoire");
 
         let tokens: Program = Program::new(
             vec![Variable::new("A", "B")],
@@ -367,11 +731,11 @@ ADD 1
             vec![Instruction::new("ADD", vec![])]
         );
 
-        let validator: Validator = Validator::new(tokens, "");
-        let result: Result<(), SicompilerError> = validator.validate_instructions(&repertoire);
+        let mut diagnostics: Diagnostics = Diagnostics::new();
+        Validator::validate_instructions(&tokens, &repertoire, &mut diagnostics);
 
-        assert!(result.is_err());
-        assert_eq!(result.unwrap_err().to_string(), "Validation error: The instruction 'ADD' must have some parameters");
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics.errors()[0].to_string(), "error[SC0014]: Invalid number of parameters in 'ADD', only has 1 but get 0");
 
         let tokens: Program = Program::new(
             vec![Variable::new("A", "B")],
@@ -379,30 +743,228 @@ ADD 1
             vec![Instruction::new("ADD", vec!["1", "2"])]
         );
 
-        let validator: Validator = Validator::new(tokens, "");
-        let result: Result<(), SicompilerError> = validator.validate_instructions(&repertoire);
+        let mut diagnostics: Diagnostics = Diagnostics::new();
+        Validator::validate_instructions(&tokens, &repertoire, &mut diagnostics);
 
-        assert!(result.is_err());
-        assert_eq!(result.unwrap_err().to_string(), "Validation error: Invalid number of parameters in 'ADD', only has 1 but get 2");
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics.errors()[0].to_string(), "error[SC0014]: Invalid number of parameters in 'ADD', only has 1 but get 2");
 
         let tokens: Program = Program::new(
             vec![Variable::new("A", "B")],
             Init::new("2"),
-            vec![Instruction::new("ADD", vec!["GGGGG"])]
+            vec![Instruction::new("ADD", vec!["G.5"])]
+        );
+
+        let mut diagnostics: Diagnostics = Diagnostics::new();
+        Validator::validate_instructions(&tokens, &repertoire, &mut diagnostics);
+
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics.errors()[0].to_string(), "error[SC0015]: Invalid parameter 'G.5' in 'ADD', the parameters must be a number (hex, 0x…, 0b…, 0o…, d'…, 'c')");
+
+        let mut tokens: Program = Program::new(
+            vec![Variable::new("A", "B")],
+            Init::new("2"),
+            vec![Instruction::new("ADD", vec!["LOOP"]), Instruction::new("ADD", vec!["GGGGG"])]
+        );
+        tokens.set_labels(vec![Label::new("LOOP", 0)]);
+
+        let mut diagnostics: Diagnostics = Diagnostics::new();
+        Validator::validate_instructions(&tokens, &repertoire, &mut diagnostics);
+
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics.errors()[0].to_string(), "error[SC0023]: The symbol 'GGGGG' is not defined");
+    }
+
+    #[test]
+    fn test_validate_symbols() {
+        let mut first: Label = Label::new("LOOP", 0);
+        first.set_span(Span::new("program.txt", 5, 1, 4));
+        let mut second: Label = Label::new("LOOP", 1);
+        second.set_span(Span::new("program.txt", 8, 1, 4));
+
+        let mut tokens: Program = Program::new(
+            vec![Variable::new("A", "B")],
+            Init::new("2"),
+            vec![Instruction::new("ADD", vec!["1"]), Instruction::new("HALT", vec![])]
+        );
+        tokens.set_labels(vec![first, Label::new("END", 1), second]);
+
+        let mut diagnostics: Diagnostics = Diagnostics::new();
+        Validator::validate_symbols(&tokens, &mut diagnostics);
+
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics.errors()[0].to_string(), "program.txt:8:1: error[SC0022]: The symbol 'LOOP' is defined more than once");
+        assert_eq!(diagnostics.errors()[0].diagnostic().notes(), &vec!["first defined at program.txt:5:1".to_string()]);
+
+        let mut variable: Variable = Variable::new("0", "3");
+        variable.set_symbol("count", Span::new("program.txt", 1, 8, 5));
+        let mut label: Label = Label::new("count", 0);
+        label.set_span(Span::new("program.txt", 5, 1, 5));
+
+        let mut tokens: Program = Program::new(vec![variable], Init::new("2"), vec![Instruction::new("HALT", vec![])]);
+        tokens.set_labels(vec![label]);
+
+        let mut diagnostics: Diagnostics = Diagnostics::new();
+        Validator::validate_symbols(&tokens, &mut diagnostics);
+
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics.errors()[0].to_string(), "program.txt:5:1: error[SC0022]: The symbol 'count' is defined more than once");
+    }
+
+    #[test]
+    fn test_validate_placement() {
+        let mut halt: Instruction = Instruction::new("HALT", vec![]);
+        halt.set_span(Span::new("program.txt", 6, 1, 4));
+
+        let tokens: Program = Program::new(vec![Variable::new("0", "0003")], Init::new("FFF"), vec![Instruction::new("ADD", vec!["0"]), halt]);
+
+        let mut diagnostics: Diagnostics = Diagnostics::new();
+        Validator::validate_placement(&tokens, &Machine::default(), &mut diagnostics);
+
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics.errors()[0].to_string(), "program.txt:6:1: error[SC0019]: The address '1000' is out of the memory, the max is 'FFF'");
+
+        let mut diagnostics: Diagnostics = Diagnostics::new();
+        Validator::validate_placement(&tokens, &Machine::new(13, 16), &mut diagnostics);
+
+        assert!(diagnostics.is_empty());
+
+        //* An init out of the memory is reported by validate_init
+        let tokens: Program = Program::new(vec![Variable::new("0", "0003")], Init::new("1000"), vec![Instruction::new("HALT", vec![])]);
+
+        let mut diagnostics: Diagnostics = Diagnostics::new();
+        Validator::validate_placement(&tokens, &Machine::default(), &mut diagnostics);
+
+        assert!(diagnostics.is_empty());
+    }
+
+    #[test]
+    fn test_validate_layout() {
+        let mut first: Variable = Variable::new("7", "5");
+        first.set_spans(Span::new("program.txt", 1, 1, 1), Span::new("program.txt", 1, 3, 1));
+        let mut second: Variable = Variable::new("0x7", "6");
+        second.set_spans(Span::new("program.txt", 2, 1, 3), Span::new("program.txt", 2, 5, 1));
+        let mut halt: Instruction = Instruction::new("HALT", vec![]);
+        halt.set_span(Span::new("program.txt", 7, 1, 4));
+
+        let tokens: Program = Program::new(vec![first, second], Init::new("6"), vec![Instruction::new("ADD", vec!["7"]), halt]);
+
+        let mut diagnostics: Diagnostics = Diagnostics::new();
+        Validator::validate_layout(&tokens, &Machine::default(), &mut diagnostics);
+
+        assert_eq!(diagnostics.len(), 2);
+        assert_eq!(diagnostics.errors()[0].to_string(), "program.txt:2:1: error[SC0043]: The address '7' is already taken by another variable or instruction");
+        assert_eq!(diagnostics.errors()[1].to_string(), "program.txt:7:1: error[SC0043]: The address '7' is already taken by another variable or instruction");
+        assert_eq!(diagnostics.errors()[1].diagnostic().notes(), &vec!["first placed at program.txt:1:1".to_string()]);
+    }
+
+    #[test]
+    fn test_validate_operands() {
+        let mut ldi: Instruction = Instruction::new("LDI", vec![]);
+        ldi.set_operands(vec![Operand::new(OperandKind::Register, 4), Operand::new(OperandKind::Immediate, 8)]);
+
+        let repertoire: Repertoire = Repertoire::new(Microprogram::default(), vec![entry("ADD"), ldi]);
+        let check = |params: Vec<&str>| {
+            let tokens: Program = Program::new(vec![Variable::new("A", "B")], Init::new("2"), vec![Instruction::new("LDI", params)]);
+            let mut diagnostics: Diagnostics = Diagnostics::new();
+            Validator::validate_instructions(&tokens, &repertoire, &mut diagnostics);
+            diagnostics.errors().iter().map(|err: &SicompilerError| err.to_string()).collect::<Vec<String>>()
+        };
+
+        assert!(check(vec!["R15", "FF"]).is_empty());
+        assert_eq!(check(vec!["1", "FF"]), vec!["error[SC0034]: Invalid parameter '1' in 'LDI', the parameter must be of kind register"]);
+        assert_eq!(check(vec!["R1", "R2"]), vec!["error[SC0034]: Invalid parameter 'R2' in 'LDI', the parameter must be of kind immediate"]);
+        assert_eq!(check(vec!["R16", "0"]), vec!["error[SC0035]: The parameter 'R16' in 'LDI' does not fit in 4 bits"]);
+        assert_eq!(check(vec!["R1", "100"]), vec!["error[SC0035]: The parameter '100' in 'LDI' does not fit in 8 bits"]);
+        assert_eq!(check(vec!["R1"]), vec!["error[SC0014]: Invalid number of parameters in 'LDI', only has 2 but get 1"]);
+
+        let tokens: Program = Program::new(vec![Variable::new("A", "B")], Init::new("2"), vec![Instruction::new("ADD", vec!["1000"])]);
+        let mut diagnostics: Diagnostics = Diagnostics::new();
+        Validator::validate_instructions(&tokens, &repertoire, &mut diagnostics);
+
+        assert_eq!(diagnostics.errors()[0].to_string(), "error[SC0035]: The parameter '1000' in 'ADD' does not fit in 12 bits");
+    }
+
+    #[test]
+    fn test_validate_constants() {
+        let repertoire: Repertoire = Repertoire::new(Microprogram::default(), vec![entry("ADD")]);
+        let check = |constants: Vec<Constant>, params: Vec<&str>| {
+            let mut tokens: Program = Program::new(vec![Variable::new("0", "SIZE")], Init::new("2"), vec![Instruction::new("ADD", params)]);
+            tokens.set_constants(constants);
+
+            let mut diagnostics: Diagnostics = Diagnostics::new();
+            Validator::check_program(&tokens, &repertoire, &mut diagnostics);
+            diagnostics.errors().iter().map(|err: &SicompilerError| err.to_string()).collect::<Vec<String>>()
+        };
+
+        assert!(check(vec![Constant::new("TABLE", "20", 0), Constant::new("SIZE", "TABLE+4", 0)], vec!["SIZE"]).is_empty());
+        assert_eq!(check(vec![Constant::new("SIZE", "TABLE+4", 0), Constant::new("TABLE", "20", 0)], vec!["TABLE"]), vec![
+            "error[SC0023]: The symbol 'SIZE' is not defined",
+            "error[SC0023]: The symbol 'TABLE' is not defined",
+        ]);
+        assert_eq!(check(vec![Constant::new("SIZE", "1", 0), Constant::new("SIZE", "2", 0)], vec!["SIZE"]), vec![
+            "error[SC0022]: The symbol 'SIZE' is defined more than once",
+        ]);
+        assert_eq!(check(vec![Constant::new("SIZE", "1000", 0)], vec!["SIZE"]), vec![
+            "error[SC0035]: The parameter 'SIZE' in 'ADD' does not fit in 12 bits",
+        ]);
+    }
+
+    #[test]
+    fn test_validate_expressions() {
+        let repertoire: Repertoire = Repertoire::new(Microprogram::default(), vec![entry("ADD"), entry("JMP")]);
+        let check = |variable: Variable, params: Vec<&str>| {
+            let mut variable: Variable = variable;
+            variable.set_symbol("table", Span::default());
+
+            let tokens: Program = Program::new(vec![variable], Init::new("2"), vec![Instruction::new("ADD", params)]);
+            let mut diagnostics: Diagnostics = Diagnostics::new();
+            Validator::validate_variables(&tokens, &Machine::default(), &mut diagnostics);
+            Validator::validate_instructions(&tokens, &repertoire, &mut diagnostics);
+            diagnostics.errors().iter().map(|err: &SicompilerError| err.code()).collect::<Vec<&str>>()
+        };
+
+        assert!(check(Variable::new("A", "table+1"), vec!["table+2"]).is_empty());
+        assert!(check(Variable::new("A", "$*2"), vec!["$-2"]).is_empty());
+        assert_eq!(check(Variable::new("A", "B"), vec!["$-3"]), vec!["SC0040"]);
+        assert_eq!(check(Variable::new("A", "B"), vec!["table+missing"]), vec!["SC0023"]);
+        assert_eq!(check(Variable::new("A", "B"), vec!["table/0"]), vec!["SC0041"]);
+        assert_eq!(check(Variable::new("A", "B"), vec!["table+FFF"]), vec!["SC0035"]);
+        assert_eq!(check(Variable::new("A", "table*"), vec!["1"]), vec!["SC0039"]);
+        assert_eq!(check(Variable::new("A", "FFFF+1"), vec!["1"]), vec!["SC0036"]);
+    }
+
+    #[test]
+    fn test_write_file_symbols() {
+        let repertoire: Repertoire = Repertoire::new(Microprogram::default(), vec![
+            entry("JMP"),
+            entry("ADD"),
+            Instruction::new("HALT", vec![])
+        ]);
+
+        let mut variable: Variable = Variable::new("1A", "3");
+        variable.set_symbol("count", Span::default());
+
+        let mut tokens: Program = Program::new(
+            vec![variable],
+            Init::new("E"),
+            vec![Instruction::new("ADD", vec!["count"]), Instruction::new("JMP", vec!["END"]), Instruction::new("HALT", vec![])]
         );
+        tokens.set_labels(vec![Label::new("END", 2)]);
 
-        let validator: Validator = Validator::new(tokens, "");
-        let result: Result<(), SicompilerError> = validator.validate_instructions(&repertoire);
+        let validator: Validator = Validator::new(tokens, "tests-files/test-symbols.txt");
+        let mut diagnostics: Diagnostics = Diagnostics::new();
+        validator.validate(&repertoire, &mut diagnostics);
 
-        assert!(result.is_err());
-        assert_eq!(result.unwrap_err().to_string(), "Validation error: Invalid parameters in 'ADD', the parameters must be in hex base");
+        assert!(diagnostics.is_empty());
+        assert_eq!(std::fs::read_to_string("tests-files/test-symbols.txt").unwrap(), "1A 3\n@\nE\n@\nADD 1A\nJMP 10\nHALT \n");
     }
 
     #[test]
     fn test_validate() {
-        let repertoire: HashMap<String, Instruction> = HashMap::from([
-            ("ADD".to_string(), Instruction::new("ADD", vec!["0x123"])),
-            ("HALT".to_string(), Instruction::new("HALT", vec![]))
+        let repertoire: Repertoire = Repertoire::new(Microprogram::default(), vec![
+            entry("ADD"),
+            Instruction::new("HALT", vec![])
         ]);
 
         let tokens: Program = Program::new(
@@ -413,7 +975,10 @@ ADD 1
 
         let validator: Validator = Validator::new(tokens, "tests-files/test.txt");
 
-        assert!(validator.validate(&repertoire).is_ok());
+        let mut diagnostics: Diagnostics = Diagnostics::new();
+        validator.validate(&repertoire, &mut diagnostics);
+
+        assert!(diagnostics.is_empty());
 
         let tokens: Program = Program::new(
             vec![Variable::new("HHHHH", "UUUUU")],
@@ -422,8 +987,22 @@ ADD 1
         );
 
         let validator: Validator = Validator::new(tokens, "tests-files/test.txt");
-        let result: Result<(), SicompilerError> = validator.validate(&repertoire);
+        let mut diagnostics: Diagnostics = Diagnostics::new();
+        validator.validate(&repertoire, &mut diagnostics);
+
+        assert_eq!(diagnostics.len(), 1);
+
+        let tokens: Program = Program::new(
+            vec![Variable::new("HHHHH", "UUUUU")],
+            Init::new("GGGGG"),
+            vec![Instruction::new("SUB", vec!["1"]), Instruction::new("HALT", vec!["1"])]
+        );
+
+        let validator: Validator = Validator::new(tokens, "tests-files/test.txt");
+        let mut diagnostics: Diagnostics = Diagnostics::new();
+        validator.validate(&repertoire, &mut diagnostics);
 
-        assert!(result.is_err());
+        assert_eq!(diagnostics.len(), 4);
+        assert_eq!(diagnostics.errors()[2].to_string(), "error[SC0013]: Invalid instruction, 'SUB' does not appear in the repertoire");
     }
 }
\ No newline at end of file
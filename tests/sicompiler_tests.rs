@@ -1,6 +1,13 @@
-use std::fs;
+use std::{fs, error::Error, io::Write, process::{Child, Command, Output, Stdio}};
 
-use sicompiler::{self, models::args::Cli, errors::error::SicompilerError};
+use clap::Parser;
+use sicompiler::{
+    self,
+    models::args::{Cli, Commands, SourceArgs, MachineArgs, CheckArgs, BuildArgs, RunArgs, FmtArgs, RepertoireArgs, DebugArgs, DisasmArgs, ImageFormat, OutputFormat},
+    models::{program::Program, repertoire::Repertoire},
+    structs::simulator::DEFAULT_MAX_STEPS,
+    errors::diagnostics::Diagnostics
+};
 
 const OUTPUT: &str = "0 0003
 1 0003
@@ -13,14 +20,19 @@ HALT
 ";
 
 #[test]
-fn compile_success() -> Result<(), SicompilerError> {
-    let cli: Cli = Cli {
-        input_path: "tests-files/test-input.txt".to_string(),
+fn compile_success() -> Result<(), Box<dyn Error>> {
+    let args: BuildArgs = BuildArgs {
+        source: SourceArgs {
+            input_path: "tests-files/test-input.txt".to_string(),
+            repertoire_path: "tests-files/test-repertoire.rep".to_string(),
+            machine: MachineArgs::default()
+        },
         output_path: "tests-files/test-out.txt".to_string(),
-        repertoire_path: "tests-files/test-repertoire.rep".to_string()
+        format: OutputFormat::Text,
+        listing_path: None
     };
 
-    sicompiler::run(&cli)?;
+    sicompiler::build(&args)?;
 
     let result: String = fs::read_to_string("tests-files/test-out.txt")?;
 
@@ -29,13 +41,324 @@ fn compile_success() -> Result<(), SicompilerError> {
     Ok(())
 }
 
+#[test]
+fn compile_bin() -> Result<(), Box<dyn Error>> {
+    let args: BuildArgs = BuildArgs {
+        source: SourceArgs {
+            input_path: "tests-files/test-input.txt".to_string(),
+            repertoire_path: "tests-files/test-repertoire.rep".to_string(),
+            machine: MachineArgs::default()
+        },
+        output_path: "tests-files/test-out.bin".to_string(),
+        format: OutputFormat::Bin,
+        listing_path: None
+    };
+
+    sicompiler::build(&args)?;
+
+    let result: Vec<u8> = fs::read("tests-files/test-out.bin")?;
+
+    //* Variables at 0, 1 and 3, then ADD 1 and HALT from the init address 6, 3 bytes per word
+    assert_eq!(result, vec![0, 0, 3, 0, 0, 3, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0x10, 0x01, 0, 0, 0]);
+
+    Ok(())
+}
+
+#[test]
+fn compile_logisim() -> Result<(), Box<dyn Error>> {
+    let args: BuildArgs = BuildArgs {
+        source: SourceArgs {
+            input_path: "tests-files/test-input.txt".to_string(),
+            repertoire_path: "tests-files/test-repertoire.rep".to_string(),
+            machine: MachineArgs::default()
+        },
+        output_path: "tests-files/test-out.hex".to_string(),
+        format: OutputFormat::Logisim,
+        listing_path: None
+    };
+
+    sicompiler::build(&args)?;
+
+    assert_eq!(fs::read_to_string("tests-files/test-out.hex")?, "v2.0 raw\n3 3 4*0 1001 0\n");
+
+    //* The fetch cycle, then HALT and ADD, in rows of 4 control words
+    assert_eq!(fs::read_to_string("tests-files/test-out-microcode.hex")?, "v2.0 raw\n4000100 201100 3000300 5*0 8000100 1100 28200\n");
+
+    Ok(())
+}
+
+#[test]
+fn compile_ihex_srec() -> Result<(), Box<dyn Error>> {
+    let mut args: BuildArgs = BuildArgs {
+        source: SourceArgs {
+            input_path: "tests-files/test-input.txt".to_string(),
+            repertoire_path: "tests-files/test-repertoire.rep".to_string(),
+            machine: MachineArgs::default()
+        },
+        output_path: "tests-files/test-out.ihex".to_string(),
+        format: OutputFormat::Ihex,
+        listing_path: None
+    };
+
+    sicompiler::build(&args)?;
+
+    //* The variables at 0, 1 and 3 and the code at 6, 3 bytes per word
+    assert_eq!(fs::read_to_string("tests-files/test-out.ihex")?, "\
+:06000000000003000003F4
+:03000900000000F4
+:06001200001001000000D7
+:00000001FF
+");
+
+    args.output_path = "tests-files/test-out.srec".to_string();
+    args.format = OutputFormat::Srec;
+
+    sicompiler::build(&args)?;
+
+    assert_eq!(fs::read_to_string("tests-files/test-out.srec")?, "\
+S0030000FC
+S1090000000003000003F0
+S1060009000000F0
+S1090012001001000000D3
+S5030003F9
+S9030012EA
+");
+
+    Ok(())
+}
+
+#[test]
+fn compile_listing() -> Result<(), Box<dyn Error>> {
+    let args: BuildArgs = BuildArgs {
+        source: SourceArgs {
+            input_path: "tests-files/test-input.txt".to_string(),
+            repertoire_path: "tests-files/test-repertoire.rep".to_string(),
+            machine: MachineArgs::default()
+        },
+        output_path: "tests-files/test-listing-out.txt".to_string(),
+        format: OutputFormat::Text,
+        listing_path: Some("tests-files/test-out.lst".to_string())
+    };
+
+    sicompiler::build(&args)?;
+
+    let listing: String = fs::read_to_string("tests-files/test-out.lst")?;
+
+    assert!(listing.contains("000   00003     1  0 0003\n"));
+    assert!(listing.contains("                5  *** multiline\n"));
+    assert!(listing.contains("006   01001    12  ADD 1 #One line comment\n"));
+    assert!(listing.contains("007   00000    13  HALT\n"));
+    assert!(listing.ends_with("SYMBOL  KIND      VALUE\n"));
+
+    Ok(())
+}
+
+#[test]
+fn disasm_image() -> Result<(), Box<dyn Error>> {
+    let args: BuildArgs = BuildArgs {
+        source: SourceArgs {
+            input_path: "tests-files/test-input.txt".to_string(),
+            repertoire_path: "tests-files/test-repertoire.rep".to_string(),
+            machine: MachineArgs::default()
+        },
+        output_path: "tests-files/test-disasm.bin".to_string(),
+        format: OutputFormat::Bin,
+        listing_path: None
+    };
+
+    sicompiler::build(&args)?;
+
+    let args: DisasmArgs = DisasmArgs {
+        input_path: "tests-files/test-disasm.bin".to_string(),
+        output_path: "tests-files/test-disasm-out.txt".to_string(),
+        repertoire_path: "tests-files/test-repertoire.rep".to_string(),
+        format: ImageFormat::Bin,
+        init: "6".to_string(),
+        machine: MachineArgs::default()
+    };
+
+    sicompiler::disasm(&args)?;
+
+    //* The variable at 3 is 0 and no instruction uses it, so it is not declared
+    assert_eq!(fs::read_to_string("tests-files/test-disasm-out.txt")?, "0 0003\n1 0003\n@\n6\n@\nADD 0001\nHALT\n");
+
+    Ok(())
+}
+
 #[test]
 fn compile_fails() {
-    let cli: Cli = Cli {
-        input_path: "tests-files/fails-files/bad-test-input.txt".to_string(),
+    let args: BuildArgs = BuildArgs {
+        source: SourceArgs {
+            input_path: "tests-files/fails-files/bad-test-input.txt".to_string(),
+            repertoire_path: "tests-files/test-repertoire.rep".to_string(),
+            machine: MachineArgs::default()
+        },
         output_path: "tests-files/fails-files/bad-test-out.txt".to_string(),
-        repertoire_path: "tests-files/test-repertoire.rep".to_string()
+        format: OutputFormat::Text,
+        listing_path: None
     };
 
-    assert!(sicompiler::run(&cli).is_err());
+    let diagnostics: Diagnostics = sicompiler::build(&args).unwrap_err();
+
+    assert_eq!(diagnostics.len(), 4);
+
+    let codes: Vec<&str> = diagnostics.errors().iter().map(|err| err.code()).collect();
+    assert_eq!(codes, vec!["SC0023", "SC0012", "SC0014", "SC0014"]);
+    assert_eq!(diagnostics.summary(), "Compilation failed due to 4 errors");
+}
+
+#[test]
+fn debug_program() -> Result<(), Box<dyn Error>> {
+    let args: DebugArgs = DebugArgs {
+        source: SourceArgs {
+            input_path: "tests-files/test-input.txt".to_string(),
+            repertoire_path: "tests-files/test-repertoire.rep".to_string(),
+            machine: MachineArgs::default()
+        }
+    };
+
+    let mut output: Vec<u8> = Vec::new();
+
+    sicompiler::debug(&args, "break :13\nc\nregs\nq\n".as_bytes(), &mut output)?;
+
+    let output: String = String::from_utf8(output)?;
+
+    assert!(output.contains("Breakpoint at 007\n007: HALT  (tests-files/test-input.txt:13:1)"));
+    assert!(output.contains("ACC=0003"));
+
+    Ok(())
+}
+
+#[test]
+fn check_program() {
+    let args: CheckArgs = CheckArgs {
+        source: SourceArgs {
+            input_path: "tests-files/fails-files/bad-test-input.txt".to_string(),
+            repertoire_path: "tests-files/test-repertoire.rep".to_string(),
+            machine: MachineArgs::default()
+        }
+    };
+
+    assert_eq!(sicompiler::check(&args).unwrap_err().len(), 4);
+    assert!(!fs::exists("tests-files/fails-files/out.txt").unwrap());
+}
+
+#[test]
+fn run_program() -> Result<(), Box<dyn Error>> {
+    let args: RunArgs = RunArgs {
+        source: SourceArgs {
+            input_path: "tests-files/test-input.txt".to_string(),
+            repertoire_path: "tests-files/test-repertoire.rep".to_string(),
+            machine: MachineArgs::default()
+        },
+        max_steps: DEFAULT_MAX_STEPS
+    };
+
+    let mut output: Vec<u8> = Vec::new();
+
+    sicompiler::run(&args, &mut output)?;
+
+    let output: String = String::from_utf8(output)?;
+
+    assert!(output.starts_with("Program halted at 007\nACC=0003 "));
+    assert!(output.ends_with("000: 00003\n001: 00003\n003: 00000\n"));
+
+    Ok(())
+}
+
+#[test]
+fn fmt_program() -> Result<(), Box<dyn Error>> {
+    let mut args: FmtArgs = FmtArgs { input_path: "tests-files/test-input.txt".to_string(), check: true };
+
+    let diagnostics: Diagnostics = sicompiler::fmt(&args).unwrap_err();
+    assert_eq!(diagnostics.errors()[0].to_string(), "tests-files/test-input.txt:12:1: error[SC0026]: The program is not formatted");
+
+    fs::copy("tests-files/test-input.txt", "tests-files/test-fmt.txt")?;
+    args.input_path = "tests-files/test-fmt.txt".to_string();
+    args.check = false;
+
+    sicompiler::fmt(&args)?;
+
+    let formatted: String = fs::read_to_string("tests-files/test-fmt.txt")?;
+    assert!(formatted.ends_with("@\n6\n@\n\nADD 1  #One line comment\nHALT\n"));
+
+    args.check = true;
+    assert!(sicompiler::fmt(&args).is_ok());
+
+    Ok(())
+}
+
+#[test]
+fn inspect_repertoire() -> Result<(), Box<dyn Error>> {
+    let args: RepertoireArgs = RepertoireArgs { repertoire_path: "tests-files/test-repertoire.rep".to_string(), machine: MachineArgs::default() };
+    let mut output: Vec<u8> = Vec::new();
+
+    sicompiler::repertoire(&args, &mut output)?;
+
+    assert_eq!(String::from_utf8(output)?, "\
+Fetch: 4000100 201100 3000300
+OPCODE  MNEMONIC  OPERANDS    MICROCODE
+0       HALT      -           0
+1       ADD       address:12  8000100 1100 28200
+");
+
+    Ok(())
+}
+
+#[test]
+fn parse_subcommands() {
+    let cli: Cli = Cli::try_parse_from(["sicompiler", "build", "-r", "rep.rep", "--format", "bin", "program.txt"]).unwrap();
+
+    assert!(matches!(cli.command, Some(Commands::Build(args)) if args.format == OutputFormat::Bin && args.output_path == "out.txt"));
+
+    let cli: Cli = Cli::try_parse_from(["sicompiler", "check", "program.txt", "--rep", "rep.rep", "--message-format", "json"]).unwrap();
+
+    assert!(matches!(cli.command, Some(Commands::Check(args)) if args.source.input_path == "program.txt"));
+    assert!(Cli::try_parse_from(["sicompiler", "--explain", "SC0001"]).unwrap().command.is_none());
+    assert!(Cli::try_parse_from(["sicompiler", "-r", "rep.rep", "program.txt"]).is_err());
+}
+
+#[test]
+fn build_from_stdin_to_stdout() -> Result<(), Box<dyn Error>> {
+    let mut child: Child = Command::new(env!("CARGO_BIN_EXE_sicompiler"))
+        .args(["build", "--rep", "tests-files/test-repertoire.rep", "--out", "-", "-"])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .spawn()?;
+
+    child.stdin.take().ok_or("no stdin")?.write_all(fs::read_to_string("tests-files/test-input.txt")?.as_bytes())?;
+
+    let output: Output = child.wait_with_output()?;
+
+    assert!(output.status.success());
+    assert_eq!(String::from_utf8(output.stdout)?, OUTPUT);
+
+    Ok(())
+}
+
+#[test]
+fn library_api() -> Result<(), Box<dyn Error>> {
+    let repertoire: Repertoire = sicompiler::parse_repertoire("$\nCB 4000100\n$\nHALT false 0\nADD true 8000100 1100 28200\n")?;
+    let program: Program = sicompiler::parse_program("0 0003 count\n@\n6\n@\nADD count\nHALT\n")?;
+
+    assert!(sicompiler::validate(&program, &repertoire).is_empty());
+    assert_eq!(sicompiler::emit(&program, &repertoire, OutputFormat::Text)?, b"0 0003\n@\n6\n@\nADD 0\nHALT \n");
+    assert_eq!(sicompiler::emit(&program, &repertoire, OutputFormat::Digital)?, b"3\n0\n0\n0\n0\n0\n1000\n0\n");
+
+    let program: Program = sicompiler::parse_program("0 0003\n@\n6\n@\nSUB 0\n")?;
+    let diagnostics: Diagnostics = sicompiler::validate(&program, &repertoire);
+
+    assert_eq!(diagnostics.errors()[0].to_string(), "<program>:5:1: error[SC0013]: Invalid instruction, 'SUB' does not appear in the repertoire");
+
+    let program: Program = sicompiler::parse_named_program("0 0003\n@\n6\n@\nSUB 0\n", "sub.txt")?;
+    let diagnostics: Diagnostics = sicompiler::validate(&program, &repertoire);
+
+    assert_eq!(diagnostics.errors()[0].to_string(), "sub.txt:5:1: error[SC0013]: Invalid instruction, 'SUB' does not appear in the repertoire");
+
+    let diagnostics: Diagnostics = sicompiler::parse_program("0 0003\n@\n6\n").unwrap_err();
+
+    assert_eq!(diagnostics.errors()[0].code(), "SC0003");
+    assert!(diagnostics.source("<program>").is_some());
+
+    Ok(())
 }
\ No newline at end of file